@@ -0,0 +1,130 @@
+//! Generate a level without launching the game and print its maze and statistics
+//!
+//! Usage:
+//! * `level-gen [--seed SEED] [--png FILE] LEVEL PART` for `CONFIG.levels[LEVEL][PART]`
+//! * `level-gen [--seed SEED] [--png FILE] --spec RON` for a level written as in `assets/config.ron`
+//!   e.g. `--spec 'KillAllKruskal2D((size: (21, 21), percent: 5.0, bug: (0, 0), entities: {}))'`
+//! * `level-gen [--seed SEED] --check` to generate every level of the config
+
+extern crate hyperzen_training;
+extern crate rand;
+extern crate ron;
+
+use hyperzen_training::level::{Generated, Level};
+use hyperzen_training::CONFIG;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: level-gen [--seed SEED] [--png FILE] (LEVEL PART | --spec RON | --check)";
+
+fn exit_with(message: String) -> ! {
+    eprintln!("{}\n{}", message, USAGE);
+    ::std::process::exit(1);
+}
+
+fn main() {
+    let mut seed = None;
+    let mut png = None;
+    let mut spec = None;
+    let mut check = false;
+    let mut indices = vec![];
+
+    let mut args = ::std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .unwrap_or_else(|| exit_with(format!("missing value for {}", name)))
+        };
+        match arg.as_str() {
+            "--seed" => {
+                let val = value("--seed");
+                seed = Some(val.parse::<u64>()
+                    .unwrap_or_else(|e| exit_with(format!("invalid seed {}: {}", val, e))));
+            }
+            "--png" => png = Some(PathBuf::from(value("--png"))),
+            "--spec" => spec = Some(value("--spec")),
+            "--check" => check = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => indices.push(arg.parse::<usize>()
+                .unwrap_or_else(|e| exit_with(format!("invalid index {}: {}", arg, e)))),
+        }
+    }
+
+    let seed = seed.unwrap_or_else(|| ::rand::random());
+    println!("seed: {}", seed);
+
+    if check {
+        for e in ::hyperzen_training::level::pack::PACK_ERRORS.iter() {
+            eprintln!("{}", e);
+        }
+        match ::hyperzen_training::level::validate_levels(seed) {
+            Ok(()) => println!("all levels are valid"),
+            Err(e) => {
+                eprintln!("invalid level: {}", e);
+                ::std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let level = match (spec, indices.as_slice()) {
+        (Some(spec), &[]) => ::ron::de::from_str::<Level>(&spec)
+            .unwrap_or_else(|e| exit_with(format!("invalid spec: {}", e))),
+        (None, &[level, part]) => CONFIG.levels.get(level)
+            .and_then(|parts| parts.get(part))
+            .cloned()
+            .unwrap_or_else(|| exit_with(format!("no level {} part {} in config", level, part))),
+        _ => exit_with("expect either a level and a part or a spec".into()),
+    };
+
+    let generated = level.generate(seed).unwrap_or_else(|e| {
+        eprintln!("generation failed: {}", e);
+        ::std::process::exit(1);
+    });
+
+    match generated {
+        Generated::Maze2D(maze) => {
+            println!("{}", maze.to_ascii());
+            let mut placed = maze.placed_entities();
+            placed.extend(maze.placed_doors().into_iter().map(|(conf, &(ref cell, _))| (conf, cell)));
+            print_stats(&placed, maze.entities());
+            print_analysis(maze.analyse());
+            if let Some(path) = png {
+                maze.export_png(&path)
+                    .unwrap_or_else(|e| exit_with(e));
+            }
+        }
+        Generated::Maze3D(maze) => {
+            println!("{}", maze.to_ascii());
+            let mut placed = maze.placed_entities();
+            placed.extend(maze.placed_doors().into_iter().map(|(conf, &(ref cell, _))| (conf, cell)));
+            print_stats(&placed, maze.entities());
+            print_analysis(maze.analyse());
+            if let Some(path) = png {
+                maze.export_png(&path)
+                    .unwrap_or_else(|e| exit_with(e));
+            }
+        }
+    }
+}
+
+/// Placed entities over requested ones
+fn print_stats<T>(
+    placed: &[(&::hyperzen_training::entity::EntityConf, T)],
+    requested: &BTreeMap<::hyperzen_training::entity::EntityConf, usize>,
+) {
+    for (conf, &nbr) in requested {
+        let count = placed.iter().filter(|&&(c, _)| c == conf).count();
+        println!("{:?}: {}/{}", conf, count, nbr);
+    }
+}
+
+fn print_analysis(analysis: Option<::hyperzen_training::maze::Analysis>) {
+    match analysis {
+        Some(analysis) => println!("{}", analysis),
+        None => println!("end can't be reached from start"),
+    }
+}
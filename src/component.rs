@@ -144,6 +144,7 @@ impl Momentum {
     }
 }
 
+#[derive(Clone)]
 pub struct StaticDraw {
     pub color: ::graphics::Color,
     pub groups: Vec<u16>,
@@ -503,15 +504,96 @@ impl FollowPlayer {
     }
 }
 
-pub struct Teleport {
-    pub action: ::resource::LevelAction,
+pub enum Teleport {
+    /// Push the action when the player enters once the level is activated
+    Action(::resource::LevelAction),
+    /// Move entering entities to this position
+    Destination(::na::Vector3<f32>),
 }
 
 impl ::specs::Component for Teleport {
     type Storage = ::specs::VecStorage<Self>;
 }
 
-#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Copy)]
+/// Cell of a maze that can open and close, see ShiftingSystem
+pub struct ShiftingWall {
+    pub cell: ::na::Vector2<isize>,
+    pub closed: bool,
+    /// Draws of the sides, removed from their entities while open
+    pub draws: Vec<(::specs::Entity, ::component::StaticDraw)>,
+}
+
+impl ::specs::Component for ShiftingWall {
+    type Storage = ::specs::VecStorage<Self>;
+}
+
+/// Shift the walls when the player enters its proximitor
+#[derive(Default)]
+pub struct ShiftTrigger {
+    /// Whether the proximitor had intersections on last update
+    pub touched: bool,
+}
+
+impl ::specs::Component for ShiftTrigger {
+    type Storage = ::specs::VecStorage<Self>;
+}
+
+/// Sensor on a cell of the room of an encounter
+pub struct RoomSensor {
+    /// Index in the encounters resource
+    pub encounter: usize,
+}
+
+impl ::specs::Component for RoomSensor {
+    type Storage = ::specs::VecStorage<Self>;
+}
+
+/// Collected when the player touches it
+#[derive(Default)]
+pub struct Orb;
+
+impl ::specs::Component for Orb {
+    type Storage = ::specs::NullStorage<Self>;
+}
+
+/// Checkpoints are reached by increasing index
+pub struct Checkpoint {
+    pub index: usize,
+}
+
+impl ::specs::Component for Checkpoint {
+    type Storage = ::specs::VecStorage<Self>;
+}
+
+/// What opens a door
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Debug)]
+pub enum DoorTrigger {
+    /// Switch touched by the player
+    Switch,
+    /// Cell entered by the player or a monster
+    Pressure,
+    /// Monster to kill
+    Kill(GeneratedEntity),
+}
+
+/// Block a cell until its key entity is deleted
+pub struct Door {
+    pub key: ::specs::Entity,
+}
+
+impl ::specs::Component for Door {
+    type Storage = ::specs::VecStorage<Self>;
+}
+
+/// Key of a door deleted when something enters its proximitor
+#[derive(Default)]
+pub struct DoorSwitch;
+
+impl ::specs::Component for DoorSwitch {
+    type Storage = ::specs::NullStorage<Self>;
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Debug)]
 pub enum GeneratedEntity {
     Avoider,
     Bouncer,
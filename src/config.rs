@@ -40,6 +40,14 @@ pub struct Config {
     pub field_of_view: f32,
     pub start_color: ::graphics::Color,
     pub end_color: ::graphics::Color,
+    pub teleporter_color: ::graphics::Color,
+    pub shift_trigger_color: ::graphics::Color,
+    pub door_color: ::graphics::Color,
+    pub door_switch_color: ::graphics::Color,
+    pub orb_color: ::graphics::Color,
+    pub checkpoint_color: ::graphics::Color,
+    pub next_checkpoint_color: ::graphics::Color,
+    pub seal_color: ::graphics::Color,
     pub activated_color: ::graphics::Color,
 
     pub depth_coef_divider: f32,
@@ -155,6 +163,11 @@ pub struct Config {
     pub weapon_angle_color: ::graphics::Color,
     pub weapon_light_ray_duration: f32,
 
+    /// Number of mazes generated before a level fails
+    pub generation_attempts: usize,
+    /// Number of levels in each room of the hall
+    pub hall_chapter_size: usize,
+    pub endless: ::level::endless::Conf,
     pub levels: Vec<Vec<::level::Level>>,
 }
 
@@ -0,0 +1,59 @@
+//! System clipboard, shared with imgui text inputs
+
+use clipboard::{ClipboardContext, ClipboardProvider};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+
+thread_local! {
+    // The context owns the copied text on some platforms so it is kept
+    static CONTEXT: RefCell<Option<ClipboardContext>> = RefCell::new(None);
+    // Text given to imgui, it is copied by imgui before the next paste
+    static PASTED: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn with_context<T, F>(f: F) -> Result<T, String>
+where
+    F: FnOnce(&mut ClipboardContext) -> Result<T, String>,
+{
+    CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        if context.is_none() {
+            *context = Some(ClipboardProvider::new().map_err(|e| format!("Failed to access clipboard: {}", e))?);
+        }
+        f(context.as_mut().unwrap())
+    })
+}
+
+/// Text of the clipboard
+pub fn get_text() -> Result<String, String> {
+    with_context(|context| context.get_contents().map_err(|e| format!("Failed to paste: {}", e)))
+}
+
+/// Replace the text of the clipboard
+pub fn set_text(text: &str) -> Result<(), String> {
+    with_context(|context| context.set_contents(text.to_string()).map_err(|e| format!("Failed to copy: {}", e)))
+}
+
+/// Make Ctrl+C and Ctrl+V of imgui text inputs use the clipboard
+pub fn connect_imgui() {
+    let io = unsafe { &mut *::imgui_sys::igGetIO() };
+    io.get_clipboard_text_fn = Some(imgui_get_text);
+    io.set_clipboard_text_fn = Some(imgui_set_text);
+}
+
+extern "C" fn imgui_get_text(_: *mut c_void) -> *const c_char {
+    let text = get_text()
+        .ok()
+        .and_then(|text| CString::new(text).ok())
+        .unwrap_or_default();
+    PASTED.with(|pasted| {
+        *pasted.borrow_mut() = text;
+        pasted.borrow().as_ptr()
+    })
+}
+
+extern "C" fn imgui_set_text(_: *mut c_void, text: *const c_char) {
+    let text = unsafe { CStr::from_ptr(text) }.to_string_lossy();
+    let _ = set_text(&text);
+}
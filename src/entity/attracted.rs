@@ -1,6 +1,6 @@
 use rand::distributions::{IndependentSample, Range};
 
-pub fn create_attracted_w(pos: ::na::Vector3<f32>, eraser: bool, world: &::specs::World) {
+pub fn create_attracted_w(pos: ::na::Vector3<f32>, eraser: bool, world: &::specs::World) -> ::specs::Entity {
     create_attracted(
         pos,
         eraser,
@@ -14,7 +14,7 @@ pub fn create_attracted_w(pos: ::na::Vector3<f32>, eraser: bool, world: &::specs
         &mut world.write(),
         &mut world.write_resource(),
         &world.read_resource(),
-    );
+    )
 }
 
 pub fn create_attracted<'a>(
@@ -30,7 +30,7 @@ pub fn create_attracted<'a>(
     contactors: &mut ::specs::WriteStorage<'a, ::component::Contactor>,
     physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
     entities: &::specs::Entities,
-) {
+) -> ::specs::Entity {
     let primitive_trans = ::graphics::resizer(
         ::CONFIG.attracted_size,
         ::CONFIG.attracted_size,
@@ -93,4 +93,5 @@ pub fn create_attracted<'a>(
     }
 
     ::component::PhysicBody::add(entity, body, bodies, physic_world);
+    entity
 }
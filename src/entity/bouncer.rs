@@ -1,4 +1,4 @@
-pub fn create_bouncer_w(pos: ::na::Vector3<f32>, eraser: bool, world: &::specs::World) {
+pub fn create_bouncer_w(pos: ::na::Vector3<f32>, eraser: bool, world: &::specs::World) -> ::specs::Entity {
     create_bouncer(
         pos,
         eraser,
@@ -12,7 +12,7 @@ pub fn create_bouncer_w(pos: ::na::Vector3<f32>, eraser: bool, world: &::specs::
         &mut world.write(),
         &mut world.write_resource(),
         &world.read_resource(),
-    );
+    )
 }
 
 pub fn create_bouncer<'a>(
@@ -28,7 +28,7 @@ pub fn create_bouncer<'a>(
     contactors: &mut ::specs::WriteStorage<'a, ::component::Contactor>,
     physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
     entities: &::specs::Entities,
-) {
+) -> ::specs::Entity {
     let primitive_trans = ::graphics::resizer(
         ::CONFIG.bouncer_size,
         ::CONFIG.bouncer_size,
@@ -83,4 +83,5 @@ pub fn create_bouncer<'a>(
     }
 
     ::component::PhysicBody::add(entity, body, bodies, physic_world);
+    entity
 }
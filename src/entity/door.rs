@@ -0,0 +1,112 @@
+/// Create the key of the door then the door
+pub fn create_door_w(
+    pos: ::na::Vector3<f32>,
+    key_pos: ::na::Vector3<f32>,
+    trigger: ::component::DoorTrigger,
+    scale: ::na::Vector3<f32>,
+    world: &::specs::World,
+) {
+    use component::DoorTrigger::*;
+    use component::GeneratedEntity;
+    let key = match trigger {
+        Switch => create_door_switch_w(key_pos, scale, false, world),
+        Pressure => create_door_switch_w(key_pos, scale, true, world),
+        Kill(GeneratedEntity::Avoider) => super::create_avoider_w(key_pos, false, world),
+        Kill(GeneratedEntity::Bouncer) => super::create_bouncer_w(key_pos, false, world),
+    };
+    create_door(
+        pos,
+        key,
+        scale,
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write_resource(),
+        &world.read_resource(),
+        &world.read_resource(),
+    );
+}
+
+/// Wall filling a cell until its key is deleted
+pub fn create_door<'a>(
+    pos: ::na::Vector3<f32>,
+    key: ::specs::Entity,
+    scale: ::na::Vector3<f32>,
+    doors: &mut ::specs::WriteStorage<'a, ::component::Door>,
+    bodies: &mut ::specs::WriteStorage<'a, ::component::PhysicBody>,
+    static_draws: &mut ::specs::WriteStorage<'a, ::component::StaticDraw>,
+    physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+    graphics: &::specs::Fetch<'a, ::resource::Graphics>,
+    entities: &::specs::Entities,
+) {
+    let entity = super::create_cell_cube(pos, scale, ::CONFIG.door_color, bodies, static_draws, physic_world, graphics, entities);
+    doors.insert(entity, ::component::Door { key });
+}
+
+pub fn create_door_switch_w(
+    pos: ::na::Vector3<f32>,
+    scale: ::na::Vector3<f32>,
+    pressure: bool,
+    world: &::specs::World,
+) -> ::specs::Entity {
+    create_door_switch(
+        pos,
+        scale,
+        pressure,
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write_resource(),
+        &world.read_resource(),
+        &world.read_resource(),
+    )
+}
+
+/// Switch touched by the player or pressure cell entered by the player or a monster
+pub fn create_door_switch<'a>(
+    pos: ::na::Vector3<f32>,
+    scale: ::na::Vector3<f32>,
+    pressure: bool,
+    door_switches: &mut ::specs::WriteStorage<'a, ::component::DoorSwitch>,
+    proximitors: &mut ::specs::WriteStorage<'a, ::component::Proximitor>,
+    sensors: &mut ::specs::WriteStorage<'a, ::component::PhysicSensor>,
+    static_draws: &mut ::specs::WriteStorage<'a, ::component::StaticDraw>,
+    physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+    graphics: &::specs::Fetch<'a, ::resource::Graphics>,
+    entities: &::specs::Entities,
+) -> ::specs::Entity {
+    let (entity, draw_pos, draw_radius) = if pressure {
+        (
+            super::create_cell_sensor(pos, scale, &[super::PLAYER_GROUP, super::MONSTER_GROUP], proximitors, sensors, physic_world, entities),
+            pos - ::na::Vector3::z() * 0.49 * scale[2],
+            ::na::Vector3::new(0.45 * scale[0], 0.45 * scale[1], 0.01 * scale[2]),
+        )
+    } else {
+        let mut group = ::nphysics::object::SensorCollisionGroups::new();
+        group.set_whitelist(&[super::PLAYER_GROUP]);
+        group.set_membership(&[super::TELEPORT_GROUP]);
+
+        let mut sensor = ::nphysics::object::Sensor::new(::ncollide::shape::Cuboid::new(scale * 0.25), None);
+        sensor.set_relative_position(::na::Isometry3::new(pos, ::na::zero()));
+        sensor.set_collision_groups(group);
+
+        let entity = entities.create();
+        proximitors.insert(entity, ::component::Proximitor::new());
+        ::component::PhysicSensor::add(entity, sensor, sensors, physic_world);
+        (entity, pos, scale * 0.1)
+    };
+
+    let (primitive, groups) = ::graphics::Primitive::Cube.instantiate();
+    ::component::StaticDraw::add(
+        entity,
+        primitive,
+        groups,
+        ::CONFIG.door_switch_color,
+        super::resized_world_trans(draw_pos, draw_radius),
+        static_draws,
+        graphics,
+    );
+    door_switches.insert(entity, ::component::DoorSwitch);
+    entity
+}
@@ -0,0 +1,52 @@
+pub fn create_room_sensor_w(pos: ::na::Vector3<f32>, scale: ::na::Vector3<f32>, encounter: usize, world: &::specs::World) {
+    create_room_sensor(
+        pos,
+        scale,
+        encounter,
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write_resource(),
+        &world.read_resource(),
+    );
+}
+
+/// Sensor on a cell starting the encounter when the player enters it
+pub fn create_room_sensor<'a>(
+    pos: ::na::Vector3<f32>,
+    scale: ::na::Vector3<f32>,
+    encounter: usize,
+    room_sensors: &mut ::specs::WriteStorage<'a, ::component::RoomSensor>,
+    proximitors: &mut ::specs::WriteStorage<'a, ::component::Proximitor>,
+    sensors: &mut ::specs::WriteStorage<'a, ::component::PhysicSensor>,
+    physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+    entities: &::specs::Entities,
+) {
+    let entity = super::create_cell_sensor(pos, scale, &[super::PLAYER_GROUP], proximitors, sensors, physic_world, entities);
+    room_sensors.insert(entity, ::component::RoomSensor { encounter });
+}
+
+pub fn create_seal_w(pos: ::na::Vector3<f32>, scale: ::na::Vector3<f32>, world: &::specs::World) -> ::specs::Entity {
+    create_seal(
+        pos,
+        scale,
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write_resource(),
+        &world.read_resource(),
+        &world.read_resource(),
+    )
+}
+
+/// Wall filling a cell at the opening of a room until its encounter is cleared
+pub fn create_seal<'a>(
+    pos: ::na::Vector3<f32>,
+    scale: ::na::Vector3<f32>,
+    bodies: &mut ::specs::WriteStorage<'a, ::component::PhysicBody>,
+    static_draws: &mut ::specs::WriteStorage<'a, ::component::StaticDraw>,
+    physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+    graphics: &::specs::Fetch<'a, ::resource::Graphics>,
+    entities: &::specs::Entities,
+) -> ::specs::Entity {
+    super::create_cell_cube(pos, scale, ::CONFIG.seal_color, bodies, static_draws, physic_world, graphics, entities)
+}
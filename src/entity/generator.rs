@@ -6,7 +6,7 @@ pub fn create_generator<'a>(
     eraser_probability: f32,
     generators: &mut ::specs::WriteStorage<'a, ::component::Generator>,
     entities: &::specs::Entities,
-) {
+) -> ::specs::Entity {
     let entity = entities.create();
     generators.insert(
         entity,
@@ -19,4 +19,5 @@ pub fn create_generator<'a>(
             eraser_probability,
         },
     );
+    entity
 }
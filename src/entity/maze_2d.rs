@@ -40,8 +40,8 @@ pub fn create_2d_maze_walls<'a>(
         entities,
     );
     super::create_floor_ceil(
-        1.0,
-        1.1,
+        maze.scale[2],
+        maze.scale[2] + 0.1,
         false,
         bodies,
         static_draws,
@@ -50,7 +50,7 @@ pub fn create_2d_maze_walls<'a>(
         entities,
     );
 
-    let mut create_wall_side_closure = |pos, x_radius, y_radius, color, physic, activated| {
+    let mut create_tile_wall_side_closure = |pos, x_radius, y_radius, color, physic, activated| {
         let entity = if physic {
             super::create_wall_side(
                 pos,
@@ -64,11 +64,11 @@ pub fn create_2d_maze_walls<'a>(
                 entities,
             )
         } else {
-            assert!(x_radius == y_radius);
             let (_, groups) = ::graphics::Primitive::Plane.instantiate();
             super::create_wall_side_draw(
                 pos,
                 x_radius,
+                y_radius,
                 color,
                 groups,
                 static_draws,
@@ -82,20 +82,60 @@ pub fn create_2d_maze_walls<'a>(
         }
     };
 
-    let minus_x_sides = maze.compute_zones(|maze, cell| {
-        let open = cell + ::na::Vector2::new(-1, 0);
+    // Sides must not be merged across wrapped borders
+    let flat = {
+        let mut flat = ::maze::Maze::new_rectangle(maze.size, maze.scale);
+        flat.walls = maze.walls.clone();
+        flat
+    };
+
+    // Copies of the maze around it so wrapped borders look seamless, copies
+    // are only drawn except their sides on the borders as bodies are wrapped
+    // back once their center crosses it. Entities aren't copied, a monster
+    // on the other side of a border is seen once the player crosses it.
+    let offsets = if maze.wrap {
+        let mut offsets = vec![];
+        for x in -1..2 {
+            for y in -1..2 {
+                offsets.push(::na::Vector3::new(
+                    (x * maze.size[0]) as f32,
+                    (y * maze.size[1]) as f32,
+                    0.0,
+                ));
+            }
+        }
+        offsets
+    } else {
+        vec![::na::zero()]
+    };
+
+    // Sides are computed for cells of size 1
+    let mut create_wall_side_closure = |pos: ::na::Isometry3<f32>, x_radius, y_radius, color, physic, activated| {
+        for offset in &offsets {
+            let pos = ::na::Isometry3::new(*offset, ::na::zero()) * pos;
+            let physic = physic && (0..2).all(|i| {
+                let coord = pos.translation.vector[i];
+                coord >= 0.0 && coord <= maze.size[i] as f32
+            });
+            let (pos, x_radius, y_radius) = super::scale_side(pos, x_radius, y_radius, &maze.scale);
+            create_tile_wall_side_closure(pos, x_radius, y_radius, color, physic, activated);
+        }
+    };
+
+    let minus_x_sides = flat.compute_zones(|_, cell| {
+        let open = maze.wrapped(cell + ::na::Vector2::new(-1, 0));
         maze.walls.contains(cell) && !maze.walls.contains(&open) && !colors.contains_key(&open)
     });
-    let plus_x_sides = maze.compute_zones(|maze, cell| {
-        let open = cell + ::na::Vector2::new(1, 0);
+    let plus_x_sides = flat.compute_zones(|_, cell| {
+        let open = maze.wrapped(cell + ::na::Vector2::new(1, 0));
         maze.walls.contains(cell) && !maze.walls.contains(&open) && !colors.contains_key(&open)
     });
-    let minus_y_sides = maze.compute_zones(|maze, cell| {
-        let open = cell + ::na::Vector2::new(0, -1);
+    let minus_y_sides = flat.compute_zones(|_, cell| {
+        let open = maze.wrapped(cell + ::na::Vector2::new(0, -1));
         maze.walls.contains(cell) && !maze.walls.contains(&open) && !colors.contains_key(&open)
     });
-    let plus_y_sides = maze.compute_zones(|maze, cell| {
-        let open = cell + ::na::Vector2::new(0, 1);
+    let plus_y_sides = flat.compute_zones(|_, cell| {
+        let open = maze.wrapped(cell + ::na::Vector2::new(0, 1));
         maze.walls.contains(cell) && !maze.walls.contains(&open) && !colors.contains_key(&open)
     });
 
@@ -146,28 +186,28 @@ pub fn create_2d_maze_walls<'a>(
     }
 
     for (pos, &(color, activated)) in colors {
-        if maze.walls.contains(&(pos + ::na::Vector2::new(-1, 0))) {
+        if maze.is_wall(&(pos + ::na::Vector2::new(-1, 0))) {
             let i = ::na::Isometry3::new(
                 ::na::Vector3::new(pos[0] as f32, pos[1] as f32 + 0.5, 0.5),
                 ::na::Vector3::y() * FRAC_PI_2,
             );
             create_wall_side_closure(i, 0.5, 0.5, color, true, activated);
         }
-        if maze.walls.contains(&(pos + ::na::Vector2::new(1, 0))) {
+        if maze.is_wall(&(pos + ::na::Vector2::new(1, 0))) {
             let i = ::na::Isometry3::new(
                 ::na::Vector3::new(pos[0] as f32 + 1.0, pos[1] as f32 + 0.5, 0.5),
                 ::na::Vector3::y() * -FRAC_PI_2,
             );
             create_wall_side_closure(i, 0.5, 0.5, color, true, activated);
         }
-        if maze.walls.contains(&(pos + ::na::Vector2::new(0, -1))) {
+        if maze.is_wall(&(pos + ::na::Vector2::new(0, -1))) {
             let i = ::na::Isometry3::new(
                 ::na::Vector3::new(pos[0] as f32 + 0.5, pos[1] as f32, 0.5),
                 ::na::Vector3::x() * -FRAC_PI_2,
             );
             create_wall_side_closure(i, 0.5, 0.5, color, true, activated);
         }
-        if maze.walls.contains(&(pos + ::na::Vector2::new(0, 1))) {
+        if maze.is_wall(&(pos + ::na::Vector2::new(0, 1))) {
             let i = ::na::Isometry3::new(
                 ::na::Vector3::new(pos[0] as f32 + 0.5, pos[1] as f32 + 1.0, 0.5),
                 ::na::Vector3::x() * FRAC_PI_2,
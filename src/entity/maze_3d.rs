@@ -68,13 +68,17 @@ pub fn create_3d_maze_walls<'a>(
                     groups[i].clone(),
                 )
             };
-            let dl_f32 =
-                ::na::Vector3::new(dl[0] as f32, dl[1] as f32, dl[2] as f32) * maze.scale / 2.;
-            let pos = ::na::Isometry3::new(maze.to_world(cell) + dl_f32, dl.axis_angle_z());
+            // Side of a cell of size 1
+            let center = ::na::Vector3::new(cell[0] as f32, cell[1] as f32, cell[2] as f32)
+                + ::na::Vector3::new(0.5, 0.5, 0.5);
+            let dl_f32 = ::na::Vector3::new(dl[0] as f32, dl[1] as f32, dl[2] as f32) / 2.;
+            let pos = ::na::Isometry3::new(center + dl_f32, dl.axis_angle_z());
+            let (pos, x_radius, y_radius) = ::entity::scale_side(pos, 0.5, 0.5, &maze.scale);
 
             ::entity::create_wall_side_draw(
                 pos,
-                maze.scale / 2.,
+                x_radius,
+                y_radius,
                 color,
                 groups,
                 static_draws,
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_3, PI};
+
+/// Half length of an hexagon edge for a distance of 1 between neighbours
+const HALF_EDGE: f32 = 0.288_675_13;
+
+/// Side of a wall cell facing `dir`, local x axis is vertical
+fn side_isometry(center: ::na::Vector3<f32>, dir: ::na::Vector3<f32>) -> ::na::Isometry3<f32> {
+    ::na::Isometry3::from_parts(
+        ::na::Translation3::from_vector(center + dir * 0.5),
+        ::na::UnitQuaternion::new(::na::Vector3::z() * dir[1].atan2(dir[0]))
+            * ::na::UnitQuaternion::new(::na::Vector3::y() * FRAC_PI_2),
+    )
+}
+
+/// An hexagon is the union of three rectangles joining its opposite edges
+fn hexagon_parts(pos: ::na::Isometry3<f32>) -> Vec<::na::Isometry3<f32>> {
+    (0..3)
+        .map(|i| pos * ::na::Isometry3::new(::na::zero(), ::na::Vector3::z() * i as f32 * FRAC_PI_3))
+        .collect()
+}
+
+fn in_maze<D>(maze: &::maze::Maze<D>, cell: &::na::VectorN<isize, D>) -> bool
+where
+    D: ::na::Dim + ::na::DimName + ::std::hash::Hash,
+    D::Value: ::std::ops::Mul<::typenum::UInt<::typenum::UTerm, ::typenum::B1>, Output = D::Value>
+        + ::generic_array::ArrayLength<isize>,
+{
+    (0..D::dim()).all(|i| cell[i] >= 0 && cell[i] < maze.size[i])
+}
+
+pub fn create_hexagonal_maze_walls_w(
+    colors: &HashMap<::na::Vector2<isize>, (::graphics::Color, bool)>,
+    maze: &::maze::Maze<::na::U2>,
+    world: &::specs::World,
+) {
+    create_hexagonal_maze_walls(
+        colors,
+        maze,
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write_resource(),
+        &world.read_resource(),
+        &world.read_resource(),
+    );
+}
+
+/// Same as create_2d_maze_walls for mazes with hexagonal topology
+pub fn create_hexagonal_maze_walls<'a>(
+    colors: &HashMap<::na::Vector2<isize>, (::graphics::Color, bool)>,
+    maze: &::maze::Maze<::na::U2>,
+    bodies: &mut ::specs::WriteStorage<'a, ::component::PhysicBody>,
+    static_draws: &mut ::specs::WriteStorage<'a, ::component::StaticDraw>,
+    activateds: &mut ::specs::WriteStorage<'a, ::component::Activated>,
+    physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+    graphics: &::specs::Fetch<'a, ::resource::Graphics>,
+    entities: &::specs::Entities,
+) {
+    super::create_floor_ceil(
+        0.0,
+        -0.1,
+        true,
+        bodies,
+        static_draws,
+        physic_world,
+        graphics,
+        entities,
+    );
+    super::create_floor_ceil(
+        maze.scale[2],
+        maze.scale[2] + 0.1,
+        false,
+        bodies,
+        static_draws,
+        physic_world,
+        graphics,
+        entities,
+    );
+
+    for cell in maze.iterate_maze() {
+        if !maze.walls.contains(&cell) {
+            continue;
+        }
+
+        for neighbour in maze.neighbours.iter().map(|n| n + cell) {
+            if maze.walls.contains(&neighbour) || !in_maze(maze, &neighbour) {
+                continue;
+            }
+
+            let (color, activated) = colors
+                .get(&neighbour)
+                .cloned()
+                .unwrap_or((::CONFIG.random_wall_color(), false));
+
+            // Positions are computed for a scale of 1
+            let pos = side_isometry(maze.unscaled(&maze.to_world(&cell)), maze.direction(&cell, &neighbour));
+            let (pos, x_radius, y_radius) = super::scale_side(pos, 0.5, HALF_EDGE, &maze.scale);
+            let entity = super::create_wall_side(
+                pos,
+                x_radius,
+                y_radius,
+                color,
+                bodies,
+                static_draws,
+                physic_world,
+                graphics,
+                entities,
+            );
+            if activated {
+                activateds.insert(entity, ::component::Activated);
+            }
+        }
+    }
+
+    for (cell, &(color, activated)) in colors {
+        let center = maze.unscaled(&maze.to_world(cell));
+        let floor = ::na::Isometry3::new(::na::Vector3::new(center[0], center[1], 0.0), ::na::zero());
+        let ceil = ::na::Isometry3::new(::na::Vector3::new(center[0], center[1], 1.0), ::na::Vector3::x() * PI);
+
+        for pos in hexagon_parts(floor).into_iter().chain(hexagon_parts(ceil)) {
+            let (pos, x_radius, y_radius) = super::scale_side(pos, 0.5, HALF_EDGE, &maze.scale);
+            let entity = super::create_wall_rectangle_draw(
+                pos,
+                x_radius,
+                y_radius,
+                color,
+                static_draws,
+                graphics,
+                entities,
+            );
+            if activated {
+                activateds.insert(entity, ::component::Activated);
+            }
+        }
+    }
+}
+
+pub fn create_hexagonal_prism_maze_walls_w(
+    colors: &HashMap<::na::Vector3<isize>, (::graphics::Color, bool)>,
+    maze: &::maze::Maze<::na::U3>,
+    world: &::specs::World,
+) {
+    create_hexagonal_prism_maze_walls(
+        colors,
+        maze,
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write_resource(),
+        &world.read_resource(),
+        &world.read_resource(),
+    );
+}
+
+/// Same as create_3d_maze_walls for mazes with hexagonal topology
+pub fn create_hexagonal_prism_maze_walls<'a>(
+    colors: &HashMap<::na::Vector3<isize>, (::graphics::Color, bool)>,
+    maze: &::maze::Maze<::na::U3>,
+    bodies: &mut ::specs::WriteStorage<'a, ::component::PhysicBody>,
+    static_draws: &mut ::specs::WriteStorage<'a, ::component::StaticDraw>,
+    physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+    graphics: &::specs::Fetch<'a, ::resource::Graphics>,
+    entities: &::specs::Entities,
+) {
+    for cell in maze.iterate_maze() {
+        if !maze.walls.contains(&cell) {
+            continue;
+        }
+
+        for neighbour in maze.neighbours.iter().map(|n| n + cell) {
+            if maze.walls.contains(&neighbour) || !in_maze(maze, &neighbour) {
+                continue;
+            }
+
+            // FIXME: activated
+            let color = colors
+                .get(&neighbour)
+                .map(|&(color, _)| color)
+                .unwrap_or(::CONFIG.random_wall_color());
+
+            let center = maze.unscaled(&maze.to_world(&cell));
+            let dir = maze.direction(&cell, &neighbour);
+            let sides = if dir[2] == 0.0 {
+                vec![side_isometry(center, dir)]
+            } else {
+                let rot = if dir[2] > 0.0 { ::na::zero() } else { ::na::Vector3::x() * PI };
+                hexagon_parts(::na::Isometry3::new(center + dir * 0.5, rot))
+            };
+
+            for pos in sides {
+                // Positions are computed for a scale of 1
+                let (pos, x_radius, y_radius) = super::scale_side(pos, 0.5, HALF_EDGE, &maze.scale);
+                super::create_wall_side(
+                    pos,
+                    x_radius,
+                    y_radius,
+                    color,
+                    bodies,
+                    static_draws,
+                    physic_world,
+                    graphics,
+                    entities,
+                );
+            }
+        }
+    }
+}
@@ -20,11 +20,18 @@ mod turret;
 mod wall;
 mod maze_2d;
 mod maze_3d;
+mod maze_hexagonal;
+mod shifting_wall;
+mod door;
+mod objective;
+mod encounter;
 mod motionless;
 mod attracted;
 mod static_draw;
+mod sensor;
 
 pub use self::static_draw::*;
+pub use self::sensor::*;
 pub use self::generator::*;
 pub use self::teleport::*;
 pub use self::weapon::*;
@@ -35,11 +42,16 @@ pub use self::turret::*;
 pub use self::wall::*;
 pub use self::maze_2d::*;
 pub use self::maze_3d::*;
+pub use self::maze_hexagonal::*;
+pub use self::shifting_wall::*;
+pub use self::door::*;
+pub use self::objective::*;
+pub use self::encounter::*;
 pub use self::depth_ball::*;
 pub use self::motionless::*;
 pub use self::attracted::*;
 
-#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum EntityConf {
     Attracted { eraser: bool },
     Avoider { eraser: bool },
@@ -51,7 +63,9 @@ pub enum EntityConf {
         salvo: usize,
         time_between_salvo_ms: usize,
         eraser_probability_percent: usize,
-    }
+    },
+    /// Block a corridor cell until its trigger is solved
+    Door { trigger: ::component::DoorTrigger },
 }
 
 impl EntityConf {
@@ -64,7 +78,34 @@ impl EntityConf {
         }
     }
 
-    pub fn create(&self, pos: ::na::Vector3<f32>, world: &mut ::specs::World) {
+    /// Doors are put on the path from start to end with their key
+    pub fn is_door(&self) -> bool {
+        use self::EntityConf::*;
+        match *self {
+            Door { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Color of the entity body
+    pub fn color(&self) -> ::graphics::Color {
+        use self::EntityConf::*;
+        match *self {
+            Attracted { .. } => ::CONFIG.attracted_color,
+            Avoider { .. } => ::CONFIG.avoider_color,
+            Bouncer { .. } => ::CONFIG.bouncer_color,
+            MotionLess { .. } => ::CONFIG.motionless_color,
+            Turret => ::CONFIG.turret_color,
+            Generator { ref generated_entity, .. } => match *generated_entity {
+                ::component::GeneratedEntity::Avoider => ::CONFIG.avoider_color,
+                ::component::GeneratedEntity::Bouncer => ::CONFIG.bouncer_color,
+            },
+            Door { .. } => ::CONFIG.door_color,
+        }
+    }
+
+    /// Return the created entity
+    pub fn create(&self, pos: ::na::Vector3<f32>, world: &mut ::specs::World) -> ::specs::Entity {
         use self::EntityConf::*;
         match *self {
             Attracted { eraser } => create_attracted_w(pos, eraser, world),
@@ -86,6 +127,7 @@ impl EntityConf {
                 &mut world.write_resource(),
                 &world.read_resource(),
             ),
+            Door { .. } => unreachable!("doors are created with their key by the level"),
         }
     }
 }
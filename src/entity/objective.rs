@@ -0,0 +1,97 @@
+pub fn create_orb_w(pos: ::na::Vector3<f32>, scale: ::na::Vector3<f32>, world: &::specs::World) {
+    create_orb(
+        pos,
+        scale,
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write_resource(),
+        &world.read_resource(),
+        &world.read_resource(),
+    );
+}
+
+/// Orb collected when the player touches it
+pub fn create_orb<'a>(
+    pos: ::na::Vector3<f32>,
+    scale: ::na::Vector3<f32>,
+    orbs: &mut ::specs::WriteStorage<'a, ::component::Orb>,
+    proximitors: &mut ::specs::WriteStorage<'a, ::component::Proximitor>,
+    sensors: &mut ::specs::WriteStorage<'a, ::component::PhysicSensor>,
+    static_draws: &mut ::specs::WriteStorage<'a, ::component::StaticDraw>,
+    physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+    graphics: &::specs::Fetch<'a, ::resource::Graphics>,
+    entities: &::specs::Entities,
+) {
+    let radius = 0.15 * scale[0].min(scale[1]).min(scale[2]);
+
+    let mut group = ::nphysics::object::SensorCollisionGroups::new();
+    group.set_whitelist(&[super::PLAYER_GROUP]);
+    group.set_membership(&[super::TELEPORT_GROUP]);
+
+    let shape = ::ncollide::shape::Cuboid::new(::na::Vector3::from_element(2.0 * radius));
+    let mut sensor = ::nphysics::object::Sensor::new(shape, None);
+    sensor.set_relative_position(::na::Isometry3::new(pos, ::na::zero()));
+    sensor.set_collision_groups(group);
+
+    let entity = entities.create();
+    let (primitive, groups) = ::graphics::Primitive::Sphere.instantiate();
+    ::component::StaticDraw::add(
+        entity,
+        primitive,
+        groups,
+        ::CONFIG.orb_color,
+        super::resized_world_trans(pos, ::na::Vector3::from_element(radius)),
+        static_draws,
+        graphics,
+    );
+    proximitors.insert(entity, ::component::Proximitor::new());
+    orbs.insert(entity, ::component::Orb);
+    ::component::PhysicSensor::add(entity, sensor, sensors, physic_world);
+}
+
+pub fn create_checkpoint_w(pos: ::na::Vector3<f32>, scale: ::na::Vector3<f32>, index: usize, world: &::specs::World) {
+    create_checkpoint(
+        pos,
+        scale,
+        index,
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write_resource(),
+        &world.read_resource(),
+        &world.read_resource(),
+    );
+}
+
+/// Plate on the floor of a cell reached when the player enters the cell
+pub fn create_checkpoint<'a>(
+    pos: ::na::Vector3<f32>,
+    scale: ::na::Vector3<f32>,
+    index: usize,
+    checkpoints: &mut ::specs::WriteStorage<'a, ::component::Checkpoint>,
+    proximitors: &mut ::specs::WriteStorage<'a, ::component::Proximitor>,
+    sensors: &mut ::specs::WriteStorage<'a, ::component::PhysicSensor>,
+    static_draws: &mut ::specs::WriteStorage<'a, ::component::StaticDraw>,
+    physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+    graphics: &::specs::Fetch<'a, ::resource::Graphics>,
+    entities: &::specs::Entities,
+) {
+    let entity = super::create_cell_sensor(pos, scale, &[super::PLAYER_GROUP], proximitors, sensors, physic_world, entities);
+    let (primitive, groups) = ::graphics::Primitive::Cube.instantiate();
+    ::component::StaticDraw::add(
+        entity,
+        primitive,
+        groups,
+        ::CONFIG.checkpoint_color,
+        super::resized_world_trans(
+            pos - ::na::Vector3::z() * 0.49 * scale[2],
+            ::na::Vector3::new(0.3 * scale[0], 0.3 * scale[1], 0.01 * scale[2]),
+        ),
+        static_draws,
+        graphics,
+    );
+    checkpoints.insert(entity, ::component::Checkpoint { index });
+}
@@ -0,0 +1,29 @@
+/// Sensor filling the cell at `pos`, its proximitor gets the bodies of `whitelist` in it
+pub fn create_cell_sensor<'a>(
+    pos: ::na::Vector3<f32>,
+    scale: ::na::Vector3<f32>,
+    whitelist: &[usize],
+    proximitors: &mut ::specs::WriteStorage<'a, ::component::Proximitor>,
+    sensors: &mut ::specs::WriteStorage<'a, ::component::PhysicSensor>,
+    physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+    entities: &::specs::Entities,
+) -> ::specs::Entity {
+    let shape = ::ncollide::shape::Cuboid::new(::na::Vector3::new(
+        0.45 * scale[0],
+        0.45 * scale[1],
+        0.5 * scale[2],
+    ));
+
+    let mut group = ::nphysics::object::SensorCollisionGroups::new();
+    group.set_whitelist(whitelist);
+    group.set_membership(&[super::TELEPORT_GROUP]);
+
+    let mut sensor = ::nphysics::object::Sensor::new(shape, None);
+    sensor.set_relative_position(::na::Isometry3::new(pos, ::na::zero()));
+    sensor.set_collision_groups(group);
+
+    let entity = entities.create();
+    proximitors.insert(entity, ::component::Proximitor::new());
+    ::component::PhysicSensor::add(entity, sensor, sensors, physic_world);
+    entity
+}
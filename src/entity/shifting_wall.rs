@@ -0,0 +1,116 @@
+use std::f32::consts::FRAC_PI_2;
+
+pub fn create_shifting_walls_w(
+    cells: &[::na::Vector2<isize>],
+    maze: &::maze::Maze<::na::U2>,
+    world: &::specs::World,
+) {
+    create_shifting_walls(
+        cells,
+        maze,
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write_resource(),
+        &world.read_resource(),
+        &world.read_resource(),
+    );
+}
+
+/// Build a wall for each cell, closed if the cell is a wall of the maze
+///
+/// Other walls of the maze must be built without those cells
+pub fn create_shifting_walls<'a>(
+    cells: &[::na::Vector2<isize>],
+    maze: &::maze::Maze<::na::U2>,
+    shifting_walls: &mut ::specs::WriteStorage<'a, ::component::ShiftingWall>,
+    bodies: &mut ::specs::WriteStorage<'a, ::component::PhysicBody>,
+    static_draws: &mut ::specs::WriteStorage<'a, ::component::StaticDraw>,
+    physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+    graphics: &::specs::Fetch<'a, ::resource::Graphics>,
+    entities: &::specs::Entities,
+) {
+    let sides = [
+        (::na::Vector2::new(-1, 0), ::na::Vector3::y() * -FRAC_PI_2),
+        (::na::Vector2::new(1, 0), ::na::Vector3::y() * FRAC_PI_2),
+        (::na::Vector2::new(0, -1), ::na::Vector3::x() * FRAC_PI_2),
+        (::na::Vector2::new(0, 1), ::na::Vector3::x() * -FRAC_PI_2),
+    ];
+
+    for cell in cells {
+        let entity = entities.create();
+
+        let mut draws = vec![];
+        for &(dl, rotation) in &sides {
+            // Sides against fixed walls are never seen
+            let neighbour = maze.wrapped(cell + dl);
+            if maze.is_wall(&neighbour) && !cells.contains(&neighbour) {
+                continue;
+            }
+
+            let pos = ::na::Isometry3::new(
+                ::na::Vector3::new(
+                    cell[0] as f32 + 0.5 + dl[0] as f32 * 0.5,
+                    cell[1] as f32 + 0.5 + dl[1] as f32 * 0.5,
+                    0.5,
+                ),
+                rotation,
+            );
+            let (pos, x_radius, y_radius) = super::scale_side(pos, 0.5, 0.5, &maze.scale);
+            let (_, groups) = ::graphics::Primitive::Plane.instantiate();
+            let draw = super::create_wall_side_draw(
+                pos,
+                x_radius,
+                y_radius,
+                ::CONFIG.random_wall_color(),
+                groups,
+                static_draws,
+                graphics,
+                entities,
+            );
+            draws.push((draw, static_draws.get(draw).unwrap().clone()));
+        }
+
+        let closed = maze.walls.contains(cell);
+        if closed {
+            let body = super::wall_cube_body(maze.to_world(cell), maze.scale / 2.0);
+            ::component::PhysicBody::add(entity, body, bodies, physic_world);
+        } else {
+            for &(draw, _) in &draws {
+                static_draws.remove(draw);
+            }
+        }
+
+        shifting_walls.insert(entity, ::component::ShiftingWall {
+            cell: cell.clone(),
+            closed,
+            draws,
+        });
+    }
+}
+
+pub fn create_shift_trigger_w(pos: ::na::Vector3<f32>, scale: ::na::Vector3<f32>, world: &::specs::World) {
+    create_shift_trigger(
+        pos,
+        scale,
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write(),
+        &mut world.write_resource(),
+        &world.read_resource(),
+    );
+}
+
+/// Sensor on a cell shifting the walls when the player enters it
+pub fn create_shift_trigger<'a>(
+    pos: ::na::Vector3<f32>,
+    scale: ::na::Vector3<f32>,
+    shift_triggers: &mut ::specs::WriteStorage<'a, ::component::ShiftTrigger>,
+    proximitors: &mut ::specs::WriteStorage<'a, ::component::Proximitor>,
+    sensors: &mut ::specs::WriteStorage<'a, ::component::PhysicSensor>,
+    physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+    entities: &::specs::Entities,
+) {
+    let entity = super::create_cell_sensor(pos, scale, &[super::PLAYER_GROUP], proximitors, sensors, physic_world, entities);
+    shift_triggers.insert(entity, ::component::ShiftTrigger::default());
+}
@@ -1,6 +1,6 @@
 use alga::general::SubsetOf;
 
-pub fn draw_score(pos: ::na::Isometry3<f32>, level: usize, world: &mut ::specs::World) {
+pub fn draw_score(pos: ::na::Isometry3<f32>, pack: usize, level: usize, world: &mut ::specs::World) {
     let radius = 0.05;
 
     let mut p = vec![
@@ -10,7 +10,7 @@ pub fn draw_score(pos: ::na::Isometry3<f32>, level: usize, world: &mut ::specs::
 
     for i in 0isize..10 {
         let save = world.read_resource::<::resource::Save>();
-        let scores = save.score(level);
+        let scores = save.score(pack, level);
 
         let best = scores.and_then(|s| s.bests.get(i as usize)).cloned();
         let best = ::graphics::Primitive::from_duration(best);
@@ -102,3 +102,34 @@ pub fn draw_number(pos: ::na::Isometry3<f32>, number: String, world: &mut ::spec
         );
     }
 }
+
+/// Draw the name of the level pack centered on `pos`, shrunk to fit in `max_width`
+pub fn draw_pack_name(pos: ::na::Isometry3<f32>, pack: usize, max_width: f32, world: &mut ::specs::World) {
+    let width = ::graphics::font::get_size(::level::pack::PACKS[pack].name.to_uppercase()) as f32
+        * ::graphics::font::POINT_CENTER_DISTANCE;
+    let radius = (max_width / width).min(0.5);
+    let total_height = 2.0;
+
+    let trans: ::na::Transform3<f32> = ::na::Similarity3::from_isometry(pos, radius).to_superset();
+    let local_trans = ::na::Translation3::new(
+        -width / 2.0,
+        -total_height * ::graphics::font::POINT_CENTER_DISTANCE,
+        0.0,
+    );
+    let world_trans = ::graphics::shader::draw1_vs::ty::World {
+        world: (trans*local_trans).unwrap().into(),
+    };
+
+    let group = ::graphics::Primitive::Text0.reserve(1).remove(0);
+    let entity = world.create_entity().build();
+
+    ::component::StaticDraw::add(
+        entity,
+        ::graphics::Primitive::pack_name_index(pack),
+        group,
+        ::graphics::Color::Red,
+        world_trans,
+        &mut world.write(),
+        &world.read_resource(),
+    );
+}
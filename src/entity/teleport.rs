@@ -1,8 +1,9 @@
-pub fn create_teleport_w(pos: ::na::Isometry3<f32>, scale: f32, action: ::resource::LevelAction, world: &::specs::World) {
+pub fn create_teleport_w(pos: ::na::Isometry3<f32>, scale: ::na::Vector3<f32>, teleport: ::component::Teleport, monsters: bool, world: &::specs::World) {
     create_teleport(
         pos,
         scale,
-        action,
+        teleport,
+        monsters,
         &mut world.write(),
         &mut world.write(),
         &mut world.write(),
@@ -13,20 +14,26 @@ pub fn create_teleport_w(pos: ::na::Isometry3<f32>, scale: f32, action: ::resour
 
 pub fn create_teleport<'a>(
     pos: ::na::Isometry3<f32>,
-    scale: f32,
-    action: ::resource::LevelAction,
+    scale: ::na::Vector3<f32>,
+    teleport: ::component::Teleport,
+    monsters: bool,
     teleports: &mut ::specs::WriteStorage<'a, ::component::Teleport>,
     proximitors: &mut ::specs::WriteStorage<'a, ::component::Proximitor>,
     sensors: &mut ::specs::WriteStorage<'a, ::component::PhysicSensor>,
     physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
     entities: &::specs::Entities,
 ) {
-    let shape = ::ncollide::shape::Cuboid::new(::na::Vector3::new(0.45 * scale, 0.45 * scale, 0.0));
+    let (x_radius, y_radius) = super::scale_radii(&pos.rotation, 0.45, 0.45, &scale);
+    let shape = ::ncollide::shape::Cuboid::new(::na::Vector3::new(x_radius, y_radius, 0.0));
     let pos =
         pos * ::na::Translation3::from_vector(::na::Vector3::new(0.0, 0.0, ::CONFIG.teleport_dl));
 
     let mut group = ::nphysics::object::SensorCollisionGroups::new();
-    group.set_whitelist(&[super::PLAYER_GROUP]);
+    if monsters {
+        group.set_whitelist(&[super::PLAYER_GROUP, super::MONSTER_GROUP]);
+    } else {
+        group.set_whitelist(&[super::PLAYER_GROUP]);
+    }
     group.set_membership(&[super::TELEPORT_GROUP]);
 
     let mut sensor = ::nphysics::object::Sensor::new(shape, None);
@@ -35,8 +42,6 @@ pub fn create_teleport<'a>(
 
     let entity = entities.create();
     proximitors.insert(entity, ::component::Proximitor::new());
-    teleports.insert(entity, ::component::Teleport {
-        action,
-    });
+    teleports.insert(entity, teleport);
     ::component::PhysicSensor::add(entity, sensor, sensors, physic_world);
 }
@@ -1,6 +1,6 @@
 use std::f32::consts::FRAC_PI_2;
 
-pub fn create_turret_w(pos: ::na::Vector3<f32>, world: &::specs::World) {
+pub fn create_turret_w(pos: ::na::Vector3<f32>, world: &::specs::World) -> ::specs::Entity {
     create_turret(
         pos,
         &mut world.write(),
@@ -9,7 +9,7 @@ pub fn create_turret_w(pos: ::na::Vector3<f32>, world: &::specs::World) {
         &mut world.write(),
         &mut world.write_resource(),
         &world.read_resource(),
-    );
+    )
 }
 
 pub fn create_turret<'a>(
@@ -20,7 +20,7 @@ pub fn create_turret<'a>(
     dynamic_graphics_assets: &mut ::specs::WriteStorage<'a, ::component::DynamicGraphicsAssets>,
     physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
     entities: &::specs::Entities,
-) {
+) -> ::specs::Entity {
     let primitive_trans =
         ::graphics::resizer(::CONFIG.turret_size, ::CONFIG.turret_size, ::CONFIG.turret_size);
 
@@ -63,4 +63,5 @@ pub fn create_turret<'a>(
         .get_mut(entity)
         .unwrap()
         .ball_in_socket(physic_world, ::na::Point3::from_coordinates(pos));
+    entity
 }
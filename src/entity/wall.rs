@@ -1,9 +1,46 @@
 use std::f32::consts::PI;
 use alga::general::SubsetOf;
 
+/// Radii of a side once cells of size 1 are scaled by `scale` on each axis
+///
+/// The side must be aligned with the axes
+pub fn scale_radii(
+    rotation: &::na::UnitQuaternion<f32>,
+    x_radius: f32,
+    y_radius: f32,
+    scale: &::na::Vector3<f32>,
+) -> (f32, f32) {
+    let factor = |axis: ::na::Vector3<f32>| {
+        let axis = rotation * axis;
+        axis[0].abs() * scale[0] + axis[1].abs() * scale[1] + axis[2].abs() * scale[2]
+    };
+    (x_radius * factor(::na::Vector3::x()), y_radius * factor(::na::Vector3::y()))
+}
+
+/// Side computed for cells of size 1 moved and resized to cells scaled by `scale`
+pub fn scale_side(
+    pos: ::na::Isometry3<f32>,
+    x_radius: f32,
+    y_radius: f32,
+    scale: &::na::Vector3<f32>,
+) -> (::na::Isometry3<f32>, f32, f32) {
+    let (x_radius, y_radius) = scale_radii(&pos.rotation, x_radius, y_radius, scale);
+    let translation = pos.translation.vector;
+    let pos = ::na::Isometry3::from_parts(
+        ::na::Translation3::new(
+            translation[0] * scale[0],
+            translation[1] * scale[1],
+            translation[2] * scale[2],
+        ),
+        pos.rotation,
+    );
+    (pos, x_radius, y_radius)
+}
+
 pub fn create_wall_side_draw<'a>(
     pos: ::na::Isometry3<f32>,
-    radius: f32,
+    x_radius: f32,
+    y_radius: f32,
     color: ::graphics::Color,
     groups: Vec<u16>,
     static_draws: &mut ::specs::WriteStorage<'a, ::component::StaticDraw>,
@@ -11,8 +48,9 @@ pub fn create_wall_side_draw<'a>(
     entities: &::specs::Entities,
 ) -> ::specs::Entity {
     let world_trans = {
-        let trans: ::na::Transform3<f32> =
-            ::na::Similarity3::from_isometry(pos, radius).to_superset();
+        let pos_trans: ::na::Transform3<f32> =
+            ::na::Similarity3::from_isometry(pos, 1.0).to_superset();
+        let trans = pos_trans * ::graphics::resizer(x_radius, y_radius, 1.0);
         ::graphics::shader::draw1_vs::ty::World {
             world: trans.unwrap().into(),
         }
@@ -32,24 +70,95 @@ pub fn create_wall_side_draw<'a>(
     entity
 }
 
-pub fn create_wall_cube_physic<'a>(
+pub fn create_wall_rectangle_draw<'a>(
+    pos: ::na::Isometry3<f32>,
+    x_radius: f32,
+    y_radius: f32,
+    color: ::graphics::Color,
+    static_draws: &mut ::specs::WriteStorage<'a, ::component::StaticDraw>,
+    graphics: &::specs::Fetch<'a, ::resource::Graphics>,
+    entities: &::specs::Entities,
+) -> ::specs::Entity {
+    let world_trans = {
+        let pos_trans: ::na::Transform3<f32> =
+            ::na::Similarity3::from_isometry(pos, 1.0).to_superset();
+        let trans = pos_trans * ::graphics::resizer(x_radius, y_radius, 1.0);
+        ::graphics::shader::draw1_vs::ty::World {
+            world: trans.unwrap().into(),
+        }
+    };
+
+    let entity = entities.create();
+    let (primitive, groups) = ::graphics::Primitive::Plane.instantiate();
+    ::component::StaticDraw::add(
+        entity,
+        primitive,
+        groups,
+        color,
+        world_trans,
+        static_draws,
+        graphics,
+    );
+    entity
+}
+
+/// Transform of a primitive of radius 1 moved to `pos` and resized to `radius` on each axis
+pub fn resized_world_trans(pos: ::na::Vector3<f32>, radius: ::na::Vector3<f32>) -> ::graphics::shader::draw1_vs::ty::World {
+    let pos_trans: ::na::Transform3<f32> =
+        ::na::Similarity3::from_isometry(::na::Isometry3::new(pos, ::na::zero()), 1.0).to_superset();
+    let trans = pos_trans * ::graphics::resizer(radius[0], radius[1], radius[2]);
+    ::graphics::shader::draw1_vs::ty::World {
+        world: trans.unwrap().into(),
+    }
+}
+
+/// Cube wall filling the cell at `pos`
+pub fn create_cell_cube<'a>(
     pos: ::na::Vector3<f32>,
-    radius: f32,
+    scale: ::na::Vector3<f32>,
+    color: ::graphics::Color,
     bodies: &mut ::specs::WriteStorage<'a, ::component::PhysicBody>,
+    static_draws: &mut ::specs::WriteStorage<'a, ::component::StaticDraw>,
     physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+    graphics: &::specs::Fetch<'a, ::resource::Graphics>,
     entities: &::specs::Entities,
-) {
+) -> ::specs::Entity {
+    let entity = entities.create();
+    let (primitive, groups) = ::graphics::Primitive::Cube.instantiate();
+    ::component::StaticDraw::add(
+        entity,
+        primitive,
+        groups,
+        color,
+        resized_world_trans(pos, scale / 2.0),
+        static_draws,
+        graphics,
+    );
+    ::component::PhysicBody::add(entity, wall_cube_body(pos, scale / 2.0), bodies, physic_world);
+    entity
+}
+
+pub fn wall_cube_body(pos: ::na::Vector3<f32>, radius: ::na::Vector3<f32>) -> ::nphysics::object::RigidBody<f32> {
     let pos = ::na::Isometry3::new(pos, ::na::zero());
     let mut group = ::nphysics::object::RigidBodyCollisionGroups::new_static();
     group.set_membership(&[super::WALL_GROUP]);
 
-    let shape = ::ncollide::shape::Cuboid::new(::na::Vector3::new(radius, radius, radius));
+    let shape = ::ncollide::shape::Cuboid::new(radius);
     let mut body = ::nphysics::object::RigidBody::new_static(shape, 10.0, 10.0);
     body.set_collision_groups(group);
     body.set_transformation(pos);
+    body
+}
 
+pub fn create_wall_cube_physic<'a>(
+    pos: ::na::Vector3<f32>,
+    radius: ::na::Vector3<f32>,
+    bodies: &mut ::specs::WriteStorage<'a, ::component::PhysicBody>,
+    physic_world: &mut ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+    entities: &::specs::Entities,
+) {
     let entity = entities.create();
-    ::component::PhysicBody::add(entity, body, bodies, physic_world);
+    ::component::PhysicBody::add(entity, wall_cube_body(pos, radius), bodies, physic_world);
 }
 
 pub fn create_wall_side<'a>(
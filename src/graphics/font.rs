@@ -22,8 +22,8 @@ pub fn build_text(text: String) -> Vec<[f32; 3]> {
     v
 }
 
-// FIXME: use it in static draw that uses font
-pub fn _get_size(text: String) -> usize {
+/// Width of the text in point center distances
+pub fn get_size(text: String) -> usize {
     let mut size = 0;
     let default_glyph = GLYPHS.get(&DEFAULT_CHAR).unwrap();
     for character in text.chars() {
@@ -32,7 +32,7 @@ mod colors;
 pub mod font;
 
 pub use self::primitives::Primitive;
-pub use self::colors::Color;
+pub use self::colors::{colors, Color};
 pub use self::primitives::GROUP_COUNTER_SIZE;
 
 pub fn resizer(x: f32, y: f32, z: f32) -> ::na::Transform3<f32> {
@@ -575,6 +575,11 @@ pub fn instance_primitives(
     primitives_buffers_def.push(vec![super::font::build_text("LAST SCORES".to_string())]);
     primitives_buffers_def.push(vec![super::font::build_text("BEST SCORES".to_string())]);
 
+    // names of level packs, see `Primitive::pack_name_index`
+    for pack in ::level::pack::PACKS.iter() {
+        primitives_buffers_def.push(vec![super::font::build_text(pack.name.to_uppercase())]);
+    }
+
     let mut final_future = Box::new(now(queue.device().clone())) as Box<GpuFuture>;
     let mut primitives_buffers = vec![];
     for primitive_buffers_def in primitives_buffers_def {
@@ -633,6 +638,11 @@ impl Primitive {
         *self as usize
     }
 
+    /// Index of the name of the level pack, its groups are the ones of a text
+    pub fn pack_name_index(pack: usize) -> usize {
+        Primitive::TextBestScores as usize + 1 + pack
+    }
+
     pub fn from_char(c: char) -> Self {
         match c {
             '0' => Primitive::Text0,
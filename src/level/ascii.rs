@@ -0,0 +1,268 @@
+//! Levels built from hand-authored mazes in `assets/mazes`
+//!
+//! Mazes are written in the format of `maze::Maze::from_ascii` with glyphs:
+//! * `#`: wall
+//! * ` ` or `.`: free cell
+//! * `S`: start cell, must have exactly one free neighbour
+//! * `E`: end cell, must have exactly one free neighbour
+//! * `T`: turret cell
+//! * `o`: entity cell
+//!
+//! 3D mazes are layers separated by an empty line, starting at z = 0.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::Read;
+use std::ops::Mul;
+use std::hash::Hash;
+use typenum;
+
+const DIRECTORY: &str = "assets/mazes";
+
+/// Mazes embedded in the executable with the packed feature
+const PACKED: &[(&str, &str)] = &[
+    ("example.txt", include_str!("../../assets/mazes/example.txt")),
+];
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Conf2D {
+    /// File name in `assets/mazes`
+    pub file: String,
+    pub entities: BTreeMap<::entity::EntityConf, usize>,
+    #[serde(default)]
+    pub objective: ::level::Objective,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Conf3D {
+    /// File name in `assets/mazes`
+    pub file: String,
+    pub entities: BTreeMap<::entity::EntityConf, usize>,
+    #[serde(default)]
+    pub objective: ::level::Objective,
+}
+
+impl Conf2D {
+    pub fn generate(&self) -> Result<::level::KruskalDecorated<::na::U2>, String> {
+        let text = load(&self.file)?;
+        ::maze::Maze::<::na::U2>::from_ascii(&text, ::na::Vector3::new(1.0, 1.0, 1.0))
+            .and_then(|(maze, marks)| ::level::KruskalDecorated::from_marks(maze, marks))
+            .and_then(|maze| maze.with_entities(&self.entities))
+            .map_err(|e| format!("Failed to parse maze \"{}\": {}", self.file, e))
+    }
+}
+
+impl Conf3D {
+    pub fn generate(&self) -> Result<::level::KruskalDecorated<::na::U3>, String> {
+        let text = load(&self.file)?;
+        ::maze::Maze::<::na::U3>::from_ascii(&text, ::na::Vector3::new(1.0, 1.0, 1.0))
+            .and_then(|(maze, marks)| ::level::KruskalDecorated::from_marks(maze, marks))
+            .and_then(|maze| maze.with_entities(&self.entities))
+            .map_err(|e| format!("Failed to parse maze \"{}\": {}", self.file, e))
+    }
+}
+
+fn load(file: &str) -> Result<String, String> {
+    if cfg!(feature = "packed") {
+        return PACKED.iter()
+            .find(|&&(name, _)| name == file)
+            .map(|&(_, text)| text.to_string())
+            .ok_or_else(|| format!("Maze \"{}\" isn't packed in the executable", file));
+    }
+    let path = format!("{}/{}", DIRECTORY, file);
+    let mut text = String::new();
+    File::open(&path)
+        .map_err(|e| format!("Failed to open \"{}\": {}", path, e))?
+        .read_to_string(&mut text)
+        .map_err(|e| format!("Failed to read \"{}\": {}", path, e))?;
+    Ok(text)
+}
+
+impl<D> ::level::KruskalDecorated<D>
+where
+    D: ::na::Dim + ::na::DimName + Hash,
+    D::Value: Mul<typenum::UInt<typenum::UTerm, typenum::B1>, Output = D::Value>
+        + ::generic_array::ArrayLength<isize> + ::generic_array::ArrayLength<f32>,
+{
+    /// Decorate a maze with the cells marked by glyphs
+    pub fn from_marks(
+        maze: ::maze::Maze<D>,
+        marks: Vec<(::na::VectorN<isize, D>, char)>,
+    ) -> Result<Self, String> {
+        let mut start_cell = None;
+        let mut end_cell = None;
+        let mut turret_cells = vec![];
+        let mut entity_cells = vec![];
+
+        for (cell, glyph) in marks {
+            match glyph {
+                'S' if start_cell.is_none() => start_cell = Some(cell),
+                'E' if end_cell.is_none() => end_cell = Some(cell),
+                'S' | 'E' => return Err(format!("multiple '{}' glyphs", glyph)),
+                'T' => turret_cells.push(cell),
+                'o' => entity_cells.push(cell),
+                _ => return Err(format!("invalid glyph '{}'", glyph)),
+            }
+        }
+
+        let start_cell = start_cell.ok_or("missing start glyph 'S'")?;
+        let end_cell = end_cell.ok_or("missing end glyph 'E'")?;
+        let start_opening = single_opening(&maze, &start_cell)
+            .ok_or("start must have exactly one free neighbour")?;
+        let end_opening = single_opening(&maze, &end_cell)
+            .ok_or("end must have exactly one free neighbour")?;
+
+        Ok(::level::KruskalDecorated {
+            maze,
+            start_cell,
+            start_opening,
+            end_cell,
+            end_opening,
+            entity_cells,
+            turret_cells,
+            teleporters: vec![],
+            teleport_monsters: false,
+            shifting_cells: vec![],
+            trigger_cells: vec![],
+            doors: vec![],
+            orbs: vec![],
+            checkpoints: vec![],
+            entities: BTreeMap::new(),
+        })
+    }
+
+    /// Set the entities placed on the marked cells, their number must match the glyphs
+    pub fn with_entities(
+        mut self,
+        entities: &BTreeMap<::entity::EntityConf, usize>,
+    ) -> Result<Self, String> {
+        if entities.iter().any(|(e, &nbr)| e.is_door() && nbr != 0) {
+            return Err("doors can't be placed in ascii mazes".into());
+        }
+        let turrets = entities.iter()
+            .filter(|&(e, _)| e.is_turret_like())
+            .map(|(_, &nbr)| nbr)
+            .sum::<usize>();
+        if turrets != self.turret_cells.len() {
+            return Err(format!(
+                "{} turret-like entities for {} 'T' glyphs",
+                turrets,
+                self.turret_cells.len()
+            ));
+        }
+        let others = entities.iter()
+            .filter(|&(e, _)| !e.is_turret_like() && !e.is_door())
+            .map(|(_, &nbr)| nbr)
+            .sum::<usize>();
+        if others != self.entity_cells.len() {
+            return Err(format!(
+                "{} entities for {} 'o' glyphs",
+                others,
+                self.entity_cells.len()
+            ));
+        }
+        self.entities = entities.clone();
+        Ok(self)
+    }
+
+    /// Inverse of `from_marks` in the ascii format, 3D mazes are layers from z = 0
+    pub fn to_ascii(&self) -> String {
+        let mut glyphs = HashMap::new();
+        for (conf, cell) in self.placed_entities() {
+            glyphs.insert(cell.clone(), if conf.is_turret_like() { 'T' } else { 'o' });
+        }
+        for &(ref door, _) in &self.doors {
+            glyphs.insert(door.clone(), 'D');
+        }
+        glyphs.insert(self.start_cell.clone(), 'S');
+        glyphs.insert(self.end_cell.clone(), 'E');
+
+        let size = &self.maze.size;
+        let layers = if D::dim() == 3 { size[2] } else { 1 };
+        let mut text = String::new();
+        for z in 0..layers {
+            if z != 0 {
+                text.push('\n');
+            }
+            for y in 0..size[1] {
+                for x in 0..size[0] {
+                    let mut cell = ::na::VectorN::<isize, D>::zeros();
+                    cell[0] = x;
+                    cell[1] = y;
+                    if D::dim() == 3 {
+                        cell[2] = z;
+                    }
+                    text.push(if self.maze.walls.contains(&cell) {
+                        '#'
+                    } else {
+                        glyphs.get(&cell).cloned().unwrap_or(' ')
+                    });
+                }
+                text.push('\n');
+            }
+        }
+        text
+    }
+}
+
+fn single_opening<D>(
+    maze: &::maze::Maze<D>,
+    cell: &::na::VectorN<isize, D>,
+) -> Option<::na::VectorN<isize, D>>
+where
+    D: ::na::Dim + ::na::DimName + Hash,
+    D::Value: Mul<typenum::UInt<typenum::UTerm, typenum::B1>, Output = D::Value>
+        + ::generic_array::ArrayLength<isize>,
+{
+    let mut free = maze.neighbours
+        .iter()
+        .map(|n| n + cell)
+        .filter(|n| !maze.walls.contains(n))
+        .collect::<Vec<_>>();
+    if free.len() == 1 {
+        free.pop()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "\
+#########
+#S#  #  #
+# # # # #
+#   #T  #
+### # ###
+#o    ###
+# #####E#
+#    o  #
+#########
+";
+
+    fn entities(turrets: usize, others: usize) -> BTreeMap<::entity::EntityConf, usize> {
+        let mut entities = BTreeMap::new();
+        entities.insert(::entity::EntityConf::Turret, turrets);
+        entities.insert(::entity::EntityConf::Bouncer { eraser: false }, others);
+        entities
+    }
+
+    fn parse(text: &str) -> Result<::level::KruskalDecorated<::na::U2>, String> {
+        ::maze::Maze::<::na::U2>::from_ascii(text, ::na::Vector3::new(1.0, 1.0, 1.0))
+            .and_then(|(maze, marks)| ::level::KruskalDecorated::from_marks(maze, marks))
+    }
+
+    #[test]
+    fn to_ascii_round_trip() {
+        let maze = parse(TEXT).unwrap().with_entities(&entities(1, 2)).unwrap();
+        assert_eq!(maze.to_ascii(), TEXT);
+    }
+
+    #[test]
+    fn entities_must_match_glyphs() {
+        assert!(parse(TEXT).unwrap().with_entities(&entities(2, 2)).is_err());
+        assert!(parse(TEXT).unwrap().with_entities(&entities(1, 1)).is_err());
+    }
+}
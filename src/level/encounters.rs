@@ -0,0 +1,194 @@
+//! 2D mazes where monsters of a room come when the player enters it
+
+use rand::distributions::{IndependentSample, Range};
+use rand::Rng;
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Encounter {
+    pub entities: BTreeMap<::entity::EntityConf, usize>,
+    /// Openings of the room are closed until its entities are killed
+    #[serde(default)]
+    pub seal: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Conf {
+    /// Maze with the entities there from the start
+    pub maze: ::level::kill_all_kruskal::Conf2D,
+    /// Each encounter is put in a different room
+    pub encounters: Vec<Encounter>,
+}
+
+impl Conf {
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> Result<::level::KruskalDecorated<::na::U2>, String> {
+        if self.encounters.iter().any(|encounter| encounter.entities.keys().any(|e| e.is_door())) {
+            return Err("doors can't be in encounters".into());
+        }
+        let maze = self.maze.generate(rng)?;
+        let rooms = rooms(&maze);
+        if rooms.len() < self.encounters.len() {
+            return Err(format!("no room for {} encounters, only {} rooms of more than {} cells", self.encounters.len(), rooms.len(), PLAYER_FOOTPRINT));
+        }
+        let sealed = self.encounters.iter().filter(|encounter| encounter.seal).count();
+        let blockers = seal_blockers(&maze);
+        let sealable = rooms.iter().filter(|room| sealable(&maze, room, &blockers)).count();
+        if sealable < sealed {
+            return Err(format!("no room for {} sealed encounters, only {} rooms without start, end, teleporters or entities at their openings", sealed, sealable));
+        }
+        Ok(maze)
+    }
+
+    /// Build the sensors of the rooms and add the encounters resource
+    pub fn create_encounters(&self, maze: &::level::KruskalDecorated<::na::U2>, seed: u64, world: &mut ::specs::World) {
+        let mut rng = ::level::seeded_rng(seed);
+        let mut rooms = rooms(maze);
+        let blockers = seal_blockers(maze);
+
+        // Sealed encounters choose first among the rooms they can seal
+        let mut assigned = vec![None; self.encounters.len()];
+        for &seal in &[true, false] {
+            for (index, _) in self.encounters.iter().enumerate().filter(|&(_, e)| e.seal == seal) {
+                let candidates = (0..rooms.len())
+                    .filter(|&i| !seal || sealable(maze, &rooms[i], &blockers))
+                    .collect::<Vec<_>>();
+                let room = rooms.swap_remove(candidates[Range::new(0, candidates.len()).ind_sample(&mut rng)]);
+                assigned[index] = Some(room);
+            }
+        }
+
+        let mut encounters = vec![];
+        for (index, (encounter, room)) in self.encounters.iter().zip(assigned).enumerate() {
+            let room = room.unwrap();
+            // Iterate in maze order as set order isn't reproducible
+            let mut cells = maze.maze.iterate_maze();
+            cells.retain(|cell| room.contains(cell));
+
+            for cell in &cells {
+                ::entity::create_room_sensor_w(maze.maze.to_world(cell), maze.maze.scale, index, world);
+            }
+
+            let mut entities = vec![];
+            for (conf, &nbr) in &encounter.entities {
+                for _ in 0..nbr {
+                    entities.push(conf.clone());
+                }
+            }
+
+            let seal_cells = if encounter.seal {
+                openings(maze, &room)
+            } else {
+                vec![]
+            };
+
+            rng.shuffle(&mut cells);
+            encounters.push(::resource::Encounter {
+                entities,
+                cells,
+                seal_cells,
+                state: ::resource::EncounterState::Waiting,
+            });
+        }
+
+        world.add_resource(::resource::Encounters(encounters));
+    }
+}
+
+/// Cells a body of the player can touch at once
+const PLAYER_FOOTPRINT: usize = 4;
+
+/// Rooms of the maze except the one of start and those the player can fill
+fn rooms(maze: &::level::KruskalDecorated<::na::U2>) -> Vec<HashSet<::na::Vector2<isize>>> {
+    let mut rooms = maze.maze.compute_room_zones();
+    rooms.retain(|room| !room.contains(&maze.start_cell) && room.len() > PLAYER_FOOTPRINT);
+    rooms
+}
+
+/// Free cells next to the room, in maze order
+fn openings(maze: &::level::KruskalDecorated<::na::U2>, room: &HashSet<::na::Vector2<isize>>) -> Vec<::na::Vector2<isize>> {
+    let mut cells = maze.maze.iterate_maze();
+    cells.retain(|cell| {
+        !room.contains(cell)
+        && !maze.maze.walls.contains(cell)
+        && maze.maze.neighbours.iter().any(|n| room.contains(&maze.maze.wrapped(n + cell)))
+    });
+    cells
+}
+
+/// Cells that can't be sealed, objectives can as they are reachable again
+/// once the encounter is cleared
+fn seal_blockers(maze: &::level::KruskalDecorated<::na::U2>) -> HashSet<::na::Vector2<isize>> {
+    let mut blockers = maze.occupied_cells();
+    for cell in maze.orbs.iter().chain(&maze.checkpoints) {
+        blockers.remove(cell);
+    }
+    blockers
+}
+
+/// All openings of the room can be sealed
+fn sealable(maze: &::level::KruskalDecorated<::na::U2>, room: &HashSet<::na::Vector2<isize>>, blockers: &HashSet<::na::Vector2<isize>>) -> bool {
+    openings(maze, room).iter().all(|cell| !blockers.contains(cell))
+}
+
+/// Create entities and seals of the encounters whose room is entered
+///
+/// Wait for the player to be in the room and off the seal cells, entities are
+/// put in the room on cells the player doesn't touch, one per cell while
+/// there are enough, on any cell of the room if the player touches them all
+pub fn create_entered_encounters(world: &mut ::specs::World) {
+    let player_cells = ::level::player_cells(world);
+    let entered = world.read_resource::<::resource::Encounters>().0.iter()
+        .enumerate()
+        .filter(|&(_, encounter)| match encounter.state {
+            ::resource::EncounterState::Entered => true,
+            _ => false,
+        })
+        .filter(|&(_, encounter)| {
+            player_cells.first().map_or(false, |cell| encounter.cells.contains(cell))
+            && player_cells.iter().all(|cell| !encounter.seal_cells.contains(cell))
+        })
+        .map(|(index, encounter)| {
+            let mut cells = encounter.cells.iter()
+                .filter(|cell| !player_cells.contains(cell))
+                .cloned()
+                .collect::<Vec<_>>();
+            if cells.is_empty() {
+                cells = encounter.cells.clone();
+            }
+            let entities = encounter.entities.iter()
+                .cloned()
+                .zip(cells.into_iter().cycle())
+                .collect::<Vec<_>>();
+            (index, entities, encounter.seal_cells.clone())
+        })
+        .collect::<Vec<_>>();
+
+    for (index, entities, seal_cells) in entered {
+        let entities = entities.into_iter()
+            .map(|(conf, cell)| {
+                let pos = match *world.read_resource::<::resource::Maze>() {
+                    ::resource::Maze::Maze2D(ref maze) => maze.to_world(&cell),
+                    ::resource::Maze::Maze3D(_) => unreachable!("encounters are in 2D mazes"),
+                };
+                conf.create(pos, world)
+            })
+            .collect();
+
+        let (positions, scale) = match *world.write_resource::<::resource::Maze>() {
+            ::resource::Maze::Maze2D(ref mut maze) => {
+                maze.walls.extend(seal_cells.iter().cloned());
+                (seal_cells.iter().map(|cell| maze.to_world(cell)).collect::<Vec<_>>(), maze.scale)
+            }
+            ::resource::Maze::Maze3D(ref maze) => (vec![], maze.scale),
+        };
+        if !positions.is_empty() {
+            *world.write_resource::<::resource::DistanceField>() = ::resource::DistanceField::new();
+        }
+        let seals = positions.into_iter()
+            .map(|pos| ::entity::create_seal_w(pos, scale, world))
+            .collect();
+
+        world.write_resource::<::resource::Encounters>().0[index].state =
+            ::resource::EncounterState::Fighting { entities, seals };
+    }
+}
@@ -0,0 +1,89 @@
+//! Endless mode chaining generated parts harder at each depth
+
+use std::collections::BTreeMap;
+
+/// Difficulty of the part at a depth
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Point {
+    pub depth: usize,
+    /// Mazes are squares of `2 * size + 1` cells
+    pub size: isize,
+    pub percent: f64,
+    /// Entities per free cell
+    pub density: f64,
+    /// Entities with their weight
+    pub pool: Vec<(::entity::EntityConf, usize)>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Conf {
+    /// Points by strictly increasing depth, parts between two points are interpolated
+    /// and parts after the last one use it
+    pub curve: Vec<Point>,
+}
+
+impl Conf {
+    /// Level of the part at `depth`, the first part is at depth 0
+    pub fn level(&self, depth: usize) -> Result<::level::Level, String> {
+        if self.curve.is_empty() {
+            return Err("endless curve has no point".into());
+        }
+        if self.curve.windows(2).any(|w| w[0].depth >= w[1].depth) {
+            return Err("endless curve depths must be strictly increasing".into());
+        }
+        let last = self.curve.iter()
+            .rposition(|point| point.depth <= depth)
+            .unwrap_or(0);
+        let from = &self.curve[last];
+        let to = self.curve.get(last + 1).unwrap_or(from);
+        let t = if to.depth > from.depth {
+            depth.saturating_sub(from.depth) as f64 / (to.depth - from.depth) as f64
+        } else {
+            0.0
+        };
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+
+        let mut weights = BTreeMap::new();
+        for &(ref conf, weight) in &from.pool {
+            weights.entry(conf.clone()).or_insert((0, 0)).0 = weight;
+        }
+        for &(ref conf, weight) in &to.pool {
+            weights.entry(conf.clone()).or_insert((0, 0)).1 = weight;
+        }
+        let pool = weights.into_iter()
+            .map(|(conf, (a, b))| (conf, lerp(a as f64, b as f64).round() as usize))
+            .filter(|&(_, weight)| weight != 0)
+            .collect::<Vec<_>>();
+
+        let densities = if pool.is_empty() {
+            vec![]
+        } else {
+            vec![::level::Density {
+                density: lerp(from.density, to.density),
+                per_room: false,
+                pool,
+                min: 1,
+                max: None,
+            }]
+        };
+
+        let size = lerp(from.size as f64, to.size as f64).round() as isize * 2 + 1;
+        Ok(::level::Level::KillAllKruskal2D(::level::kill_all_kruskal::Conf2D {
+            algorithm: ::maze::Algorithm::Kruskal,
+            topology: ::maze::Topology::Square,
+            mask: ::mask::Mask::Full,
+            wrap: None,
+            recipe: None,
+            floors: 1,
+            teleporters: 0,
+            teleport_monsters: false,
+            size: (size, size),
+            scale: (1.0, 1.0, 1.0),
+            percent: lerp(from.percent, to.percent),
+            bug: (0, 0),
+            entities: BTreeMap::new(),
+            densities,
+            objective: ::level::Objective::KillAll,
+        }))
+    }
+}
@@ -0,0 +1,103 @@
+//! Top-down PNG rendering of decorated mazes
+//!
+//! Cells are drawn on their grid coordinates, rows as in the ascii format.
+//! 3D mazes are drawn as a grid of z slices, from left to right then top to bottom.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::ops::Mul;
+use std::hash::Hash;
+use std::path::Path;
+use png::HasParameters;
+use typenum;
+
+/// Size of a cell in pixels
+const CELL: usize = 8;
+/// Size of markers of openings and entities in pixels
+const MARKER: usize = 4;
+
+impl<D> ::level::KruskalDecorated<D>
+where
+    D: ::na::Dim + ::na::DimName + Hash,
+    D::Value: Mul<typenum::UInt<typenum::UTerm, typenum::B1>, Output = D::Value>
+        + ::generic_array::ArrayLength<isize> + ::generic_array::ArrayLength<f32>,
+{
+    /// Write the maze with its start, end, teleporters, turrets and entities in their configured colors
+    pub fn export_png(&self, path: &Path) -> Result<(), String> {
+        let palette = ::graphics::colors();
+        let rgba = |color: ::graphics::Color| {
+            let color = palette[color as usize];
+            [
+                (color[0] * 255.0) as u8,
+                (color[1] * 255.0) as u8,
+                (color[2] * 255.0) as u8,
+                (color[3] * 255.0) as u8,
+            ]
+        };
+
+        let size = &self.maze.size;
+        let slices = if D::dim() == 3 { size[2] as usize } else { 1 };
+        let columns = (slices as f64).sqrt().ceil() as usize;
+        let rows = (slices + columns - 1) / columns;
+
+        // One empty cell between slices
+        let slice_width = (size[0] as usize + 1) * CELL;
+        let slice_height = (size[1] as usize + 1) * CELL;
+        let width = columns * slice_width - CELL;
+        let height = rows * slice_height - CELL;
+
+        let mut data = vec![0u8; width * height * 4];
+        {
+            let mut fill = |cell: &::na::VectorN<isize, D>, color: [u8; 4], side: usize| {
+                let slice = if D::dim() == 3 { cell[2] as usize } else { 0 };
+                let offset = (CELL - side) / 2;
+                let x0 = (slice % columns) * slice_width + cell[0] as usize * CELL + offset;
+                let y0 = (slice / columns) * slice_height + cell[1] as usize * CELL + offset;
+                for y in y0..y0 + side {
+                    for x in x0..x0 + side {
+                        let i = (y * width + x) * 4;
+                        data[i..i + 4].copy_from_slice(&color);
+                    }
+                }
+            };
+
+            let wall = rgba(::CONFIG.wall_color[0]);
+            let free = rgba(::graphics::Color::White);
+            for cell in self.maze.iterate_maze() {
+                let color = if self.maze.walls.contains(&cell) { wall } else { free };
+                fill(&cell, color, CELL);
+            }
+
+            fill(&self.start_cell, rgba(::CONFIG.start_color), CELL);
+            fill(&self.start_opening, rgba(::CONFIG.start_color), MARKER);
+            fill(&self.end_cell, rgba(::CONFIG.end_color), CELL);
+            fill(&self.end_opening, rgba(::CONFIG.end_color), MARKER);
+            for &(ref cell, ref opening) in self.teleporters.iter().flat_map(|pair| pair.iter()) {
+                fill(cell, rgba(::CONFIG.teleporter_color), CELL);
+                fill(opening, rgba(::CONFIG.teleporter_color), MARKER);
+            }
+
+            for (conf, cell) in self.placed_entities() {
+                fill(cell, rgba(conf.color()), MARKER);
+            }
+            for cell in &self.orbs {
+                fill(cell, rgba(::CONFIG.orb_color), MARKER);
+            }
+            for cell in &self.checkpoints {
+                fill(cell, rgba(::CONFIG.checkpoint_color), MARKER);
+            }
+            for (conf, &(ref cell, ref key)) in self.placed_doors() {
+                fill(cell, rgba(conf.color()), CELL);
+                fill(key, rgba(conf.color()), MARKER);
+            }
+        }
+
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create \"{}\": {}", path.display(), e))?;
+        let mut encoder = ::png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+        encoder.set(::png::ColorType::RGBA).set(::png::BitDepth::Eight);
+        encoder.write_header()
+            .and_then(|mut writer| writer.write_image_data(&data))
+            .map_err(|e| format!("Failed to encode \"{}\": {}", path.display(), e))
+    }
+}
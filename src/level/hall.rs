@@ -1,21 +1,58 @@
 use std::collections::HashMap;
 use std::f32::consts::FRAC_PI_2;
+use std::ops::Range;
 
-pub fn create_hall(world: &mut ::specs::World) {
-    let number_of_levels = ::CONFIG.levels.len() as isize;
-    let levels_on_top = number_of_levels / 2;
-    let levels_on_side =  number_of_levels - levels_on_top;
+/// Height of the inside of chapter rooms
+const ROOM_HEIGHT: isize = 4;
+/// Back wall, teleport wall, inside, gate wall and corridor of a row of rooms
+const ROW_HEIGHT: isize = ROOM_HEIGHT + 5;
 
-    let size = ::na::Vector2::new(levels_on_top.max(1)*3+3, levels_on_side*3+3);
-    let mut maze = ::maze::Maze::new_rectangle(size, 1.0);
-    maze.circle();
-    maze.extend(1);
-    maze.circle();
+/// Hub with a room for each chapter of the pack
+///
+/// Rooms have a teleport per level on their top wall and a gate on their
+/// bottom wall, the gate opens once all levels of previous chapters have a
+/// score. Each row of rooms opens on a corridor, corridors are joined by a
+/// passage on the left down to the lobby where the player starts.
+///
+/// The hall of the campaign links to the hall of every other pack on the
+/// left wall of the lobby, other halls link back to it.
+pub fn create_hall(pack: usize, world: &mut ::specs::World) {
+    let levels = ::level::pack::PACKS[pack].levels.len();
+    let chapter_size = ::CONFIG.hall_chapter_size.max(1);
+    let chapters = ((levels + chapter_size - 1) / chapter_size).max(1);
+    let columns = (chapters as f32).sqrt().ceil() as usize;
+    let rows = (chapters + columns - 1) / columns;
 
+    let halls = if pack == ::level::pack::CAMPAIGN {
+        (0..::level::pack::PACKS.len()).filter(|&p| p != pack).collect::<Vec<_>>()
+    } else {
+        vec![::level::pack::CAMPAIGN]
+    };
+
+    let room_width = chapter_size as isize * 3;
+    let lobby_y = rows as isize * ROW_HEIGHT;
+    let lobby_height = (halls.len() as isize * 3).max(3);
+    let size = ::na::Vector2::new(
+        5 + columns as isize * (room_width + 1),
+        lobby_y + lobby_height + 2,
+    );
+
+    let mut maze = ::maze::Maze::new_rectangle(size, ::na::Vector3::new(1.0, 1.0, 1.0));
+    maze.walls = maze.iterate_maze().into_iter().collect();
     let maze_size = maze.size.clone();
 
     let mut maze_colors = HashMap::new();
 
+    // Carve lobby, corridors and the passage joining them
+    carve(&mut maze, 2..maze_size[0] - 1, lobby_y..lobby_y + lobby_height);
+    for row in 0..rows as isize {
+        let y = row * ROW_HEIGHT;
+        carve(&mut maze, 2..maze_size[0] - 1, y + ROOM_HEIGHT + 3..y + ROW_HEIGHT);
+        if row != 0 {
+            carve(&mut maze, 2..4, y..y + ROOM_HEIGHT + 3);
+        }
+    }
+
     // Build Player
     let start_cell = ::na::Vector2::new(maze_size[0] - 3, maze_size[1] - 2);
     maze_colors.insert(start_cell, (::CONFIG.start_color, false));
@@ -27,68 +64,104 @@ pub fn create_hall(world: &mut ::specs::World) {
         [(-dir[1] as f32).atan2(dir[0] as f32), 0.0];
     ::entity::create_player_w(player_pos, false, world);
 
-    // Build Teleport
-    let teleport_cells = (0isize..levels_on_top)
-        .map(|i| (::na::Vector2::new(maze_size[0] - 4 - i*3, 1), ::na::Vector3::new(-FRAC_PI_2, 0.0, 0.0)))
-        .chain((0..levels_on_side)
-            .map(|i| (::na::Vector2::new(1, i*3+3), ::na::Vector3::new(0.0, FRAC_PI_2, 0.0))));
+    // Build chapter rooms with their gate and teleports
+    for chapter in 0..chapters {
+        let x = 4 + (chapter % columns) as isize * (room_width + 1);
+        let y = (chapter / columns) as isize * ROW_HEIGHT;
+        carve(&mut maze, x + 1..x + 1 + room_width, y + 2..y + 2 + ROOM_HEIGHT);
+
+        let gate_cell = ::na::Vector2::new(x + 1 + room_width / 2, y + 2 + ROOM_HEIGHT);
+        let opened = {
+            let save = world.read_resource::<::resource::Save>();
+            (0..chapter * chapter_size).all(|level| save.score(pack, level).is_some())
+        };
+        if opened {
+            maze.walls.remove(&gate_cell);
+        } else {
+            maze_colors.insert(gate_cell, (::CONFIG.door_color, false));
+        }
+
+        let first_level = chapter * chapter_size;
+        for i in first_level..levels.min(first_level + chapter_size) {
+            let teleport_cell = ::na::Vector2::new(x + 2 + (i - first_level) as isize * 3, y + 1);
+            let activated = i == 0 || world.read_resource::<::resource::Save>().score(pack, i-1).is_some();
+
+            maze_colors.insert(teleport_cell, (::CONFIG.end_color, activated));
+            maze.walls.remove(&teleport_cell);
+
+            ::entity::draw_score(
+                ::na::Isometry3::new(
+                    maze.to_world(&teleport_cell)+::na::Vector3::new(-0.7, 0.5, 0.3),
+                    ::na::Vector3::new(::std::f32::consts::FRAC_PI_2, 0.0, 0.0)
+                ) * ::na::UnitQuaternion::new(::na::Vector3::new(0.0, ::std::f32::consts::PI, 0.0)),
+                pack,
+                i,
+                world,
+            );
+
+            ::entity::draw_number(
+                ::na::Isometry3::new(
+                    maze.to_world(&teleport_cell)+::na::Vector3::new(0.0, -0.5, 0.0),
+                    ::na::Vector3::new(::std::f32::consts::FRAC_PI_2, 0.0, 0.0)
+                ) * ::na::UnitQuaternion::new(::na::Vector3::new(0.0, ::std::f32::consts::PI, 0.0)),
+                format!("{}", i+1),
+                world,
+            );
+
+            if activated {
+                ::entity::create_teleport_w(
+                    ::na::Isometry3::new(
+                        maze.to_world(&teleport_cell),
+                        ::na::Vector3::new(-FRAC_PI_2, 0.0, 0.0),
+                    ),
+                    maze.scale,
+                    ::component::Teleport::Action(::resource::LevelAction::Level(pack, i)),
+                    false,
+                    world,
+                );
+            }
+        }
+    }
 
-    for (i, (teleport_cell, teleport_dir)) in teleport_cells.enumerate() {
-        let activated = i == 0 || world.read_resource::<::resource::Save>().score(i-1).is_some();
+    // Build teleports to other halls
+    for (i, &hall) in halls.iter().enumerate() {
+        let teleport_cell = ::na::Vector2::new(1, lobby_y + 1 + i as isize * 3);
 
-        maze_colors.insert(teleport_cell, (::CONFIG.end_color, activated));
+        maze_colors.insert(teleport_cell, (::CONFIG.end_color, true));
         maze.walls.remove(&teleport_cell);
 
-        let score_pos = if teleport_dir[1] == 0.0 {
+        ::entity::draw_pack_name(
             ::na::Isometry3::new(
-                maze.to_world(&teleport_cell)+::na::Vector3::new(-0.7, 0.5, 0.3),
-                ::na::Vector3::new(::std::f32::consts::FRAC_PI_2, 0.0, 0.0)
-            ) * ::na::UnitQuaternion::new(::na::Vector3::new(0.0, ::std::f32::consts::PI, 0.0))
-        } else {
-            ::na::Isometry3::new(
-                maze.to_world(&teleport_cell)+::na::Vector3::new(0.5, 0.7, 0.3),
+                maze.to_world(&teleport_cell)+::na::Vector3::new(-0.5, 0.0, 0.0),
                 ::na::Vector3::new(::std::f32::consts::FRAC_PI_2, 0.0, 0.0)
-            ) * ::na::UnitQuaternion::new(::na::Vector3::new(0.0, ::std::f32::consts::FRAC_PI_2, 0.0))
-        };
-
-        ::entity::draw_score(
-            score_pos,
-            i,
+            ) * ::na::UnitQuaternion::new(::na::Vector3::new(0.0, ::std::f32::consts::FRAC_PI_2, 0.0)),
+            hall,
+            2.5,
             world,
         );
 
-        let number_pos = if teleport_dir[1] == 0.0 {
+        ::entity::create_teleport_w(
             ::na::Isometry3::new(
-                maze.to_world(&teleport_cell)+::na::Vector3::new(0.0, -0.5, 0.0),
-                ::na::Vector3::new(::std::f32::consts::FRAC_PI_2, 0.0, 0.0)
-            ) * ::na::UnitQuaternion::new(::na::Vector3::new(0.0, ::std::f32::consts::PI, 0.0))
-        } else {
-            ::na::Isometry3::new(
-                maze.to_world(&teleport_cell)+::na::Vector3::new(-0.5, 0.0, 0.0),
-                ::na::Vector3::new(::std::f32::consts::FRAC_PI_2, 0.0, 0.0)
-            ) * ::na::UnitQuaternion::new(::na::Vector3::new(0.0, ::std::f32::consts::FRAC_PI_2, 0.0))
-        };
-
-        ::entity::draw_number(
-            number_pos,
-            format!("{}", i+1),
+                maze.to_world(&teleport_cell),
+                ::na::Vector3::new(0.0, FRAC_PI_2, 0.0),
+            ),
+            maze.scale,
+            ::component::Teleport::Action(::resource::LevelAction::Hall(hall)),
+            false,
             world,
         );
-
-        if activated {
-            ::entity::create_teleport_w(
-                ::na::Isometry3::new(
-                    maze.to_world(&teleport_cell),
-                    teleport_dir,
-                ),
-                maze.scale,
-                ::resource::LevelAction::Level(i),
-                world,
-            );
-        }
     }
 
     // Build Maze
     ::entity::create_2d_maze_walls_w(&maze_colors, &maze, world);
     world.add_resource(::resource::Maze::Maze2D(maze));
 }
+
+/// Remove walls of the area
+fn carve(maze: &mut ::maze::Maze<::na::U2>, xs: Range<isize>, ys: Range<isize>) {
+    for x in xs {
+        for y in ys.clone() {
+            maze.walls.remove(&::na::Vector2::new(x, y));
+        }
+    }
+}
@@ -1,181 +1,128 @@
-use util::ConvCoord;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use rand::Rng;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Conf2D {
+    #[serde(default)]
+    pub algorithm: ::maze::Algorithm,
+    /// Square or hexagonal, hexagonal mazes have their own algorithm and no bug
+    #[serde(default)]
+    pub topology: ::maze::Topology,
+    #[serde(default)]
+    pub mask: ::mask::Mask,
+    /// Connect opposite borders with this percent of border openings
+    #[serde(default)]
+    pub wrap: Option<f64>,
+    /// Operations shaping the generated maze, replace `level::default_recipe` and `wrap`
+    #[serde(default)]
+    pub recipe: Option<Vec<::maze::Operation>>,
+    /// Mazes of the same configuration linked one to the next by teleporters
+    #[serde(default)]
+    pub floors: usize,
+    /// Teleporter pairs between random cells
+    #[serde(default)]
+    pub teleporters: usize,
+    /// Teleporters also move monsters
+    #[serde(default)]
+    pub teleport_monsters: bool,
     pub size: (isize, isize),
+    /// Size of cells on x and y, and height of walls
+    #[serde(default = "default_scale")]
+    pub scale: (f32, f32, f32),
     pub percent: f64,
     pub bug: (isize, isize),
-    pub entities: HashMap<::entity::EntityConf, usize>,
+    pub entities: BTreeMap<::entity::EntityConf, usize>,
+    /// Entities added with a count depending on the generated maze
+    #[serde(default)]
+    pub densities: Vec<::level::Density>,
+    /// What activates the teleport, killing all monsters by default
+    #[serde(default)]
+    pub objective: ::level::Objective,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Conf3D {
+    #[serde(default)]
+    pub algorithm: ::maze::Algorithm,
+    /// Square or hexagonal, hexagonal mazes have their own algorithm and no bug
+    #[serde(default)]
+    pub topology: ::maze::Topology,
+    #[serde(default)]
+    pub mask: ::mask::Mask,
+    /// Operations shaping the generated maze, replace `level::default_recipe`
+    #[serde(default)]
+    pub recipe: Option<Vec<::maze::Operation>>,
     pub size: (isize, isize, isize),
+    /// Size of cells on each axis
+    #[serde(default = "default_scale")]
+    pub scale: (f32, f32, f32),
     pub percent: f64,
     pub bug: (isize, isize, isize),
-    pub entities: HashMap<::entity::EntityConf, usize>,
+    pub entities: BTreeMap<::entity::EntityConf, usize>,
+    /// Entities added with a count depending on the generated maze
+    #[serde(default)]
+    pub densities: Vec<::level::Density>,
+    /// What activates the teleport, killing all monsters by default
+    #[serde(default)]
+    pub objective: ::level::Objective,
 }
 
-// FIXME: factorize
-impl Conf2D {
-    pub fn create(&self, world: &mut ::specs::World) {
-        let maze = {
-            let (turrets, entities) = self.entities.iter()
-                .fold((0, 0), |mut acc, (e, nbr)| {
-                    if e.is_turret_like() {
-                        acc.0 += nbr;
-                    } else {
-                        acc.1 += nbr;
-                    }
-                    acc
-                });
+fn default_scale() -> (f32, f32, f32) {
+    (1.0, 1.0, 1.0)
+}
 
-            ::level::KruskalDecorated::new(
-                ::na::Vector2::new(self.size.0, self.size.1),
-                self.percent,
-                ::na::Vector2::new(self.bug.0, self.bug.1),
-                turrets,
-                entities,
-            )
+impl Conf2D {
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> Result<::level::KruskalDecorated<::na::U2>, String> {
+        let size = ::na::Vector2::new(self.size.0, self.size.1);
+        let carvable = self.mask.carvable(&size)?;
+        let recipe = match self.recipe {
+            Some(_) if self.wrap.is_some() => return Err("wrap must be an operation of the recipe".into()),
+            Some(ref recipe) => recipe.clone(),
+            None => ::level::default_recipe(self.wrap),
         };
 
-        // Build walls
-        let mut maze_colors = HashMap::new();
-        maze_colors.insert(maze.start_cell, (::CONFIG.start_color, false));
-        maze_colors.insert(maze.end_cell, (::CONFIG.end_color, true));
-
-        ::entity::create_2d_maze_walls_w(&maze_colors, &maze.maze, world);
-
-        // Build teleport
-        ::entity::create_teleport_w(
-            ::na::Isometry3::new(
-                maze.maze.to_world(&maze.end_cell),
-                (maze.end_opening - maze.end_cell).axis_angle_z(),
-            ),
-            maze.maze.scale,
-            ::resource::LevelAction::Next,
-            world,
-        );
-
-        // Build player
-        let dir = maze.start_opening - maze.start_cell;
-        let player_pos = maze.maze.to_world(&maze.start_cell)
-            - 0.2 * ::na::Vector3::new(dir[0] as f32, dir[1] as f32, 0.0);
-        world.write_resource::<::resource::PlayerControl>().pointer =
-            [(-dir[1] as f32).atan2(dir[0] as f32), 0.0];
-        ::entity::create_player_w(player_pos, false, world);
-
-        // Build turrets
-        self.entities.iter()
-            .filter(|&(e, _)| e.is_turret_like())
-            .flat_map(|(e, &nbr)| {
-                let mut v = vec![];
-                v.resize(nbr, e);
-                v
-            })
-            .zip(maze.turret_cells.iter())
-            .for_each(|(conf, cell)| {
-                let pos = maze.maze.to_world(cell);
-                conf.create(pos, world);
-            });
-
-        // Build entities
-        self.entities.iter()
-            .filter(|&(e, _)| !e.is_turret_like())
-            .flat_map(|(e, &nbr)| {
-                let mut v = vec![];
-                v.resize(nbr, e);
-                v
-            })
-            .zip(maze.entity_cells.iter())
-            .for_each(|(conf, cell)| {
-                let pos = maze.maze.to_world(cell);
-                conf.create(pos, world);
-            });
-
-        // Build maze resource
-        world.add_resource(::resource::Maze::Maze2D(maze.maze));
+        let mut maze = ::level::KruskalDecorated::new(::level::Params {
+            algorithm: self.algorithm,
+            topology: self.topology,
+            size,
+            percent: self.percent,
+            bug: ::na::Vector2::new(self.bug.0, self.bug.1),
+            scale: ::na::Vector3::new(self.scale.0, self.scale.1, self.scale.2),
+            recipe: &recipe,
+            carvable: &carvable,
+            floors: self.floors,
+            teleporters: self.teleporters,
+            teleport_monsters: self.teleport_monsters,
+            entities: &self.entities,
+            densities: &self.densities,
+        }, rng)?;
+        maze.place_doors(rng)?;
+        Ok(maze)
     }
 }
 
 impl Conf3D {
-    pub fn create(&self, world: &mut ::specs::World) {
-        let maze = {
-            let (turrets, entities) = self.entities.iter()
-                .fold((0, 0), |mut acc, (e, nbr)| {
-                    if e.is_turret_like() {
-                        acc.0 += nbr;
-                    } else {
-                        acc.1 += nbr;
-                    }
-                    acc
-                });
-
-            ::level::KruskalDecorated::new(
-                ::na::Vector3::new(self.size.0, self.size.1, self.size.2),
-                self.percent,
-                ::na::Vector3::new(self.bug.0, self.bug.1, self.bug.2),
-                turrets,
-                entities,
-            )
-        };
-
-        // Build walls
-        let mut maze_colors = HashMap::new();
-        maze_colors.insert(maze.start_cell, (::CONFIG.start_color, false));
-        maze_colors.insert(maze.end_cell, (::CONFIG.end_color, true));
-
-        ::entity::create_3d_maze_walls_w(&maze_colors, &maze.maze, world);
-
-        // Build teleport
-        ::entity::create_teleport_w(
-            ::na::Isometry3::new(
-                maze.maze.to_world(&maze.end_cell),
-                (maze.end_opening - maze.end_cell).axis_angle_z(),
-            ),
-            maze.maze.scale,
-            ::resource::LevelAction::Next,
-            world,
-        );
-
-        // Build player
-        let dir = maze.start_opening - maze.start_cell;
-        let player_pos = maze.maze.to_world(&maze.start_cell)
-            - 0.2 * ::na::Vector3::new(dir[0] as f32, dir[1] as f32, 0.0);
-        world.write_resource::<::resource::PlayerControl>().pointer =
-            [(-dir[1] as f32).atan2(dir[0] as f32), 0.0];
-        ::entity::create_player_w(player_pos, true, world);
-
-        // Build turrets
-        self.entities.iter()
-            .filter(|&(e, _)| e.is_turret_like())
-            .flat_map(|(e, &nbr)| {
-                let mut v = vec![];
-                v.resize(nbr, e);
-                v
-            })
-            .zip(maze.turret_cells.iter())
-            .for_each(|(conf, cell)| {
-                let pos = maze.maze.to_world(cell);
-                conf.create(pos, world);
-            });
-
-        // Build entities
-        self.entities.iter()
-            .filter(|&(e, _)| !e.is_turret_like())
-            .flat_map(|(e, &nbr)| {
-                let mut v = vec![];
-                v.resize(nbr, e);
-                v
-            })
-            .zip(maze.entity_cells.iter())
-            .for_each(|(conf, cell)| {
-                let pos = maze.maze.to_world(cell);
-                conf.create(pos, world);
-            });
-
-        // Build maze resource
-        world.add_resource(::resource::Maze::Maze3D(maze.maze));
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> Result<::level::KruskalDecorated<::na::U3>, String> {
+        let size = ::na::Vector3::new(self.size.0, self.size.1, self.size.2);
+        let carvable = self.mask.carvable(&size)?;
+        let recipe = self.recipe.clone().unwrap_or_else(|| ::level::default_recipe(None));
+
+        let mut maze = ::level::KruskalDecorated::new(::level::Params {
+            algorithm: self.algorithm,
+            topology: self.topology,
+            size,
+            percent: self.percent,
+            bug: ::na::Vector3::new(self.bug.0, self.bug.1, self.bug.2),
+            scale: ::na::Vector3::new(self.scale.0, self.scale.1, self.scale.2),
+            recipe: &recipe,
+            carvable: &carvable,
+            floors: 1,
+            teleporters: 0,
+            teleport_monsters: false,
+            entities: &self.entities,
+            densities: &self.densities,
+        }, rng)?;
+        maze.place_doors(rng)?;
+        Ok(maze)
     }
 }
@@ -1,5 +1,15 @@
 pub mod kill_all_kruskal;
+pub mod ascii;
+pub mod export;
+pub mod shifting;
+pub mod waves;
+pub mod encounters;
+pub mod pack;
+pub mod endless;
 use rand::distributions::{IndependentSample, Range};
+use rand::{Rng, SeedableRng, StdRng};
+use specs::Join;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::Mul;
 use std::hash::Hash;
 use typenum;
@@ -11,15 +21,210 @@ pub use self::hall::create_hall;
 pub enum Level {
     KillAllKruskal2D(kill_all_kruskal::Conf2D),
     KillAllKruskal3D(kill_all_kruskal::Conf3D),
+    Ascii2D(ascii::Conf2D),
+    Ascii3D(ascii::Conf3D),
+    Shifting2D(shifting::Conf),
+    Waves2D(waves::Conf),
+    Encounters2D(encounters::Conf),
+}
+
+/// What activates the teleport of a level
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum Objective {
+    /// Kill all attracted, avoiders, bouncers and motionless and all waves
+    KillAll,
+    /// Collect this number of orbs scattered in rooms
+    Collect(usize),
+    /// Reach this number of checkpoints in order
+    Checkpoints(usize),
+    /// Erase this percent of the screen
+    Erase(f32),
+}
+
+impl Default for Objective {
+    fn default() -> Self {
+        Objective::KillAll
+    }
+}
+
+/// Entities drawn from a weighted pool, their number scales with the maze
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Density {
+    /// Entities per free cell, or per room if `per_room`
+    pub density: f64,
+    #[serde(default)]
+    pub per_room: bool,
+    /// Entities with their weight
+    pub pool: Vec<(::entity::EntityConf, usize)>,
+    /// Fewest entities drawn whatever the maze size
+    #[serde(default)]
+    pub min: usize,
+    /// Most entities drawn, unbounded if none
+    #[serde(default)]
+    pub max: Option<usize>,
+}
+
+impl Density {
+    /// Add entities drawn for `maze` to `entities`
+    fn resolve<D, R>(&self, maze: &::maze::Maze<D>, entities: &mut BTreeMap<::entity::EntityConf, usize>, rng: &mut R)
+    where
+        D: ::na::Dim + ::na::DimName + Hash,
+        D::Value: Mul<typenum::UInt<typenum::UTerm, typenum::B1>, Output = D::Value>
+            + ::generic_array::ArrayLength<isize>,
+        R: Rng,
+    {
+        let units = if self.per_room {
+            maze.compute_room_zones().len()
+        } else {
+            maze.iterate_maze().iter().filter(|cell| !maze.walls.contains(*cell)).count()
+        };
+        let mut count = ((self.density * units as f64).round() as usize).max(self.min);
+        if let Some(max) = self.max {
+            count = count.min(max);
+        }
+
+        let total = self.pool.iter().map(|&(_, weight)| weight).sum::<usize>();
+        for _ in 0..count {
+            let mut draw = Range::new(0, total).ind_sample(rng);
+            for &(ref conf, weight) in &self.pool {
+                if draw < weight {
+                    *entities.entry(conf.clone()).or_insert(0) += 1;
+                    break;
+                }
+                draw -= weight;
+            }
+        }
+    }
+}
+
+/// Maze of a level decorated with its cells, generated without the world
+pub enum Generated {
+    Maze2D(KruskalDecorated<::na::U2>),
+    Maze3D(KruskalDecorated<::na::U3>),
 }
 
 impl Level {
-    pub fn create(&self, world: &mut ::specs::World) {
+    /// The same seed always generates the same level
+    pub fn create(&self, seed: u64, world: &mut ::specs::World) -> Result<(), String> {
+        let generated = self.generate(seed)?;
+        world.add_resource(::resource::Objective(self.objective().clone()));
+        match generated {
+            Generated::Maze2D(maze) => {
+                match *self {
+                    Level::Shifting2D(ref conf) => world.add_resource(conf.shifting_walls(&maze, seed)),
+                    Level::Waves2D(ref conf) => world.add_resource(conf.waves(&maze, seed)),
+                    Level::Encounters2D(ref conf) => conf.create_encounters(&maze, seed, world),
+                    _ => (),
+                }
+                maze.create(world)
+            }
+            Generated::Maze3D(maze) => maze.create(world),
+        }
+        Ok(())
+    }
+
+    pub fn generate(&self, seed: u64) -> Result<Generated, String> {
+        let mut rng = seeded_rng(seed);
+        let mut generated = match *self {
+            Level::KillAllKruskal2D(ref conf) => Generated::Maze2D(conf.generate(&mut rng)?),
+            Level::KillAllKruskal3D(ref conf) => Generated::Maze3D(conf.generate(&mut rng)?),
+            Level::Ascii2D(ref conf) => Generated::Maze2D(conf.generate()?),
+            Level::Ascii3D(ref conf) => Generated::Maze3D(conf.generate()?),
+            Level::Shifting2D(ref conf) => Generated::Maze2D(conf.generate(&mut rng)?),
+            Level::Waves2D(ref conf) => Generated::Maze2D(conf.generate(&mut rng)?),
+            Level::Encounters2D(ref conf) => Generated::Maze2D(conf.generate(&mut rng)?),
+        };
+        match generated {
+            Generated::Maze2D(ref mut maze) => maze.place_objective(self.objective(), &mut rng)?,
+            Generated::Maze3D(ref mut maze) => maze.place_objective(self.objective(), &mut rng)?,
+        }
+        Ok(generated)
+    }
+
+    pub fn objective(&self) -> &Objective {
         match *self {
-            Level::KillAllKruskal2D(ref conf) => conf.create(world),
-            Level::KillAllKruskal3D(ref conf) => conf.create(world),
+            Level::KillAllKruskal2D(ref conf) => &conf.objective,
+            Level::KillAllKruskal3D(ref conf) => &conf.objective,
+            Level::Ascii2D(ref conf) => &conf.objective,
+            Level::Ascii3D(ref conf) => &conf.objective,
+            Level::Shifting2D(ref conf) => &conf.maze.objective,
+            Level::Waves2D(ref conf) => &conf.maze.objective,
+            Level::Encounters2D(ref conf) => &conf.maze.objective,
+        }
+    }
+}
+
+/// Generate every level of the config and of the packs once, and endless
+/// parts at the depths of its curve
+pub fn validate_levels(seed: u64) -> Result<(), String> {
+    for pack in pack::PACKS.iter() {
+        for (i, parts) in pack.levels.iter().enumerate() {
+            for (j, level) in parts.iter().enumerate() {
+                level.generate(seed)
+                    .map_err(|e| format!("{} level {} part {} with seed {}: {}", pack.name, i, j, seed, e))?;
+            }
         }
     }
+    for point in &::CONFIG.endless.curve {
+        ::CONFIG.endless.level(point.depth)
+            .and_then(|level| level.generate(seed))
+            .map_err(|e| format!("endless depth {} with seed {}: {}", point.depth, seed, e))?;
+    }
+    Ok(())
+}
+
+/// Operations applied to generated mazes when the level has no recipe
+pub fn default_recipe(wrap: Option<f64>) -> Vec<::maze::Operation> {
+    use ::maze::Operation::*;
+    if let Some(wrap) = wrap {
+        // Border is kept on non wrapped axes only
+        vec![OpenWrap(wrap), Circle, FillSmallests, FillDeadCorridors(0.0)]
+    } else {
+        vec![Reduce(1), Circle, FillSmallests, FillDeadCorridors(0.0), Extend(1), Circle]
+    }
+}
+
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::from_seed(&[seed as usize][..])
+}
+
+/// Cell of the player first then other cells touched by its body, none in 3D mazes
+fn player_cells(world: &::specs::World) -> Vec<::na::Vector2<isize>> {
+    let players = world.read::<::component::Player>();
+    let bodies = world.read::<::component::PhysicBody>();
+    let physic_world = world.read_resource::<::resource::PhysicWorld>();
+    let pos = match (&players, &bodies).join().next() {
+        Some((_, body)) => body.get(&physic_world).position().translation.vector,
+        None => return vec![],
+    };
+    match *world.read_resource::<::resource::Maze>() {
+        ::resource::Maze::Maze2D(ref maze) => maze.touched_cells(&pos, ::CONFIG.player_radius),
+        ::resource::Maze::Maze3D(_) => vec![],
+    }
+}
+
+/// Settings of a maze generated by `KruskalDecorated::new`
+pub struct Params<'a, D>
+where
+    D: ::na::Dim + ::na::DimName + Hash,
+    D::Value: Mul<typenum::UInt<typenum::UTerm, typenum::B1>, Output = D::Value>
+        + ::generic_array::ArrayLength<isize>,
+{
+    pub algorithm: ::maze::Algorithm,
+    pub topology: ::maze::Topology,
+    pub size: ::na::VectorN<isize, D>,
+    pub percent: f64,
+    pub bug: ::na::VectorN<isize, D>,
+    pub scale: ::na::Vector3<f32>,
+    pub recipe: &'a [::maze::Operation],
+    /// Cells of a floor that can be carved
+    pub carvable: &'a HashSet<::na::VectorN<isize, D>>,
+    pub floors: usize,
+    /// Pairs of teleporters linking random cells
+    pub teleporters: usize,
+    pub teleport_monsters: bool,
+    pub entities: &'a BTreeMap<::entity::EntityConf, usize>,
+    pub densities: &'a [Density],
 }
 
 pub struct KruskalDecorated<D>
@@ -35,6 +240,20 @@ where
     end_opening: ::na::VectorN<isize, D>,
     entity_cells: Vec<::na::VectorN<isize, D>>,
     turret_cells: Vec<::na::VectorN<isize, D>>,
+    /// Linked pairs of teleporter cells with their openings
+    teleporters: Vec<[(::na::VectorN<isize, D>, ::na::VectorN<isize, D>); 2]>,
+    teleport_monsters: bool,
+    /// Cells built as walls that open and close
+    shifting_cells: Vec<::na::VectorN<isize, D>>,
+    /// Cells shifting the walls when the player enters them
+    trigger_cells: Vec<::na::VectorN<isize, D>>,
+    /// Door cells with the cell of their key
+    doors: Vec<(::na::VectorN<isize, D>, ::na::VectorN<isize, D>)>,
+    orbs: Vec<::na::VectorN<isize, D>>,
+    /// Checkpoints in the order they must be reached
+    checkpoints: Vec<::na::VectorN<isize, D>>,
+    /// Entities of the level with counts resolved for this maze
+    entities: BTreeMap<::entity::EntityConf, usize>,
 }
 
 impl<D> KruskalDecorated<D>
@@ -48,52 +267,179 @@ where
     /// in rooms cells we put turret exept in front of end and start room
     /// in all cells exept turret and start room we put entities
     /// and all other things
-    pub fn new(size: ::na::VectorN<isize, D>, percent: f64, bug: ::na::VectorN<isize, D>, turrets: usize, entities: usize) -> Self {
-        let mut rng = ::rand::thread_rng();
-        loop {
-            // Generate general maze
-            let mut maze = ::maze::Maze::kruskal(size.clone(), percent, bug.clone(), 1.0);
-            maze.reduce(1);
-            maze.circle();
-            maze.fill_smallests();
+    ///
+    /// Each floor is generated then shaped by the operations of `recipe`.
+    ///
+    /// Floors are put one after the other along x, start is on the first floor
+    /// and end on the last one. Each floor is linked to the next one by a
+    /// teleporter pair, then `teleporters` pairs link random cells.
+    ///
+    /// Entities are the ones of `entities` and the ones drawn by `densities`
+    /// once the maze is generated.
+    ///
+    /// Fail if no maze with start, end and teleporters is generated in `CONFIG.generation_attempts`
+    pub fn new<R: Rng>(params: Params<D>, rng: &mut R) -> Result<Self, String> {
+        let Params {
+            algorithm,
+            topology,
+            size,
+            percent,
+            bug,
+            scale,
+            recipe,
+            carvable,
+            floors,
+            teleporters,
+            teleport_monsters,
+            entities,
+            densities,
+        } = params;
+        if densities.iter().any(|density| density.pool.iter().all(|&(_, weight)| weight == 0)) {
+            return Err("density pools must have a positive weight".into());
+        }
+        let wrap = recipe.iter().any(|operation| match *operation {
+            ::maze::Operation::OpenWrap(_) => true,
+            _ => false,
+        });
+        if scale.iter().any(|&s| s <= 0.0) {
+            return Err(format!("scale must be positive, got {:?}", scale.iter().collect::<Vec<_>>()));
+        }
+        if topology == ::maze::Topology::Hexagonal && scale[0] != scale[1] {
+            return Err("hexagonal mazes must have the same scale on x and y".into());
+        }
+        if topology == ::maze::Topology::Hexagonal && algorithm != ::maze::Algorithm::Kruskal {
+            return Err(format!("hexagonal mazes have their own algorithm, got {:?}", algorithm));
+        }
+        if topology == ::maze::Topology::Hexagonal && bug.iter().any(|&b| b != 0) {
+            return Err("hexagonal mazes can't have a bug".into());
+        }
+        if wrap && D::dim() != 2 {
+            return Err("only 2D mazes can wrap".into());
+        }
+        if wrap && topology == ::maze::Topology::Hexagonal {
+            return Err("hexagonal mazes can't wrap".into());
+        }
+        let floors = floors.max(1);
+        if wrap && floors > 1 {
+            return Err("mazes with several floors can't wrap".into());
+        }
 
-            while maze.fill_dead_corridors() {}
+        // Borders added by the recipe, cells are shifted by this much from the mask
+        let mut shift = 0;
+        for operation in recipe {
+            match *operation {
+                ::maze::Operation::Reduce(width) => {
+                    if width <= 0 || size.iter().any(|&s| s + 2 * shift < 2 * width) {
+                        return Err(format!("recipe can't reduce by {} a maze of size {:?}", width, size.iter().map(|&s| s + 2 * shift).collect::<Vec<_>>()));
+                    }
+                    shift -= width;
+                }
+                ::maze::Operation::Extend(width) => shift += width,
+                _ => (),
+            }
+        }
+        let floor_width = size[0] + 2 * shift;
+
+        let floor_of = |cell: &::na::VectorN<isize, D>| (cell[0] / floor_width) as usize;
+        let carvable_in_floor = |cell: &::na::VectorN<isize, D>| {
+            let mut cell = cell.clone();
+            cell[0] %= floor_width;
+            for i in 0..D::dim() {
+                cell[i] -= shift;
+            }
+            carvable.contains(&cell)
+        };
 
-            maze.extend(1);
-            maze.circle();
+        let mut missing_start = 0;
+        let mut missing_end = 0;
+        let mut missing_teleporter = 0;
+        'attempts: for _ in 0..::CONFIG.generation_attempts {
+            // Generate general maze
+            let mut maze = Self::generate_floor(algorithm, topology, size.clone(), percent, bug.clone(), scale, recipe, carvable, rng);
+            for _ in 1..floors {
+                let floor = Self::generate_floor(algorithm, topology, size.clone(), percent, bug.clone(), scale, recipe, carvable, rng);
+                maze.append_floor(floor);
+            }
 
             // Start
-            let mut dig_start = maze.dig_cells(1, |_| true);
-            if dig_start.first().is_none() { continue }
+            let mut dig_start = maze.dig_cells(1, |cell| carvable_in_floor(cell) && floor_of(cell) == 0, rng);
+            if dig_start.first().is_none() {
+                missing_start += 1;
+                continue
+            }
             let (start_cell, start_opening) = dig_start.remove(0);
 
             // End
-            let mut dig_end = maze.dig_cells(1, |_| true);
-            if dig_end.first().is_none() { continue }
+            let mut dig_end = maze.dig_cells(1, |cell| carvable_in_floor(cell) && floor_of(cell) == floors - 1, rng);
+            if dig_end.first().is_none() {
+                missing_end += 1;
+                continue
+            }
             let (end_cell, end_opening) = dig_end.remove(0);
 
+            // Teleporters, the chain between floors keeps them mutually reachable
+            let mut teleporter_floors = (1..floors)
+                .map(|floor| (Some(floor - 1), Some(floor)))
+                .collect::<Vec<_>>();
+            teleporter_floors.resize(floors - 1 + teleporters, (None, None));
+
+            let mut teleporter_pairs = vec![];
+            for (from, to) in teleporter_floors {
+                let mut pair = vec![];
+                for floor in vec![from, to] {
+                    let mut dig = maze.dig_cells(1, |cell| {
+                        carvable_in_floor(cell) && floor.map(|f| floor_of(cell) == f).unwrap_or(true)
+                    }, rng);
+                    if dig.first().is_none() {
+                        missing_teleporter += 1;
+                        continue 'attempts
+                    }
+                    pair.push(dig.remove(0));
+                }
+                teleporter_pairs.push([pair[0].clone(), pair[1].clone()]);
+            }
+            let teleporter_cells = teleporter_pairs.iter()
+                .flat_map(|pair| pair.iter())
+                .flat_map(|&(ref cell, ref opening)| vec![cell.clone(), opening.clone()])
+                .collect::<HashSet<_>>();
+
+            // Resolve densities
+            let mut resolved = entities.clone();
+            for density in densities {
+                density.resolve(&maze, &mut resolved, rng);
+            }
+            let (turrets, others) = resolved.iter()
+                .filter(|&(e, _)| !e.is_door())
+                .fold((0, 0), |mut acc, (e, nbr)| {
+                    if e.is_turret_like() {
+                        acc.0 += nbr;
+                    } else {
+                        acc.1 += nbr;
+                    }
+                    acc
+                });
+
             // Put turrets
             let cells = maze.compute_inner_room_zones()
                 .iter()
-                .cloned()
-                .filter_map(|mut room| {
-                    room.retain(|cell| {
-                        (start_cell.clone() - cell.clone()).iter().fold(0, |acc, c| acc + c.pow(2)) > 5_isize.pow(2)
+                .filter_map(|room| {
+                    // Iterate in maze order as set order isn't reproducible
+                    let mut cells = maze.iterate_maze();
+                    cells.retain(|cell| {
+                        room.contains(cell)
+                        && (start_cell.clone() - cell.clone()).iter().fold(0, |acc, c| acc + c.pow(2)) > 5_isize.pow(2)
                         && *cell != start_cell
                         && *cell != start_opening
                         && *cell != end_cell
                         && *cell != end_opening
+                        && !teleporter_cells.contains(cell)
                         && maze.is_neighbouring_wall(cell)
                     });
-                    if room.is_empty() {
+                    if cells.is_empty() {
                         None
                     } else {
-                        let cell = room.iter()
-                            .skip(Range::new(0, room.len()).ind_sample(&mut rng))
-                            .next()
-                            .unwrap()
-                            .clone();
-                        Some(cell)
+                        let index = Range::new(0, cells.len()).ind_sample(rng);
+                        Some(cells.swap_remove(index))
                     }
                 })
                 .collect::<Vec<_>>();
@@ -113,22 +459,23 @@ where
                 && *cell != start_opening
                 && *cell != end_cell
                 && *cell != end_opening
+                && !teleporter_cells.contains(cell)
                 && !turret_cells.contains(cell)
             });
 
             let mut entity_cells = vec![];
 
-            for _ in 0..entities {
+            for _ in 0..others {
                 if cells.is_empty() {
                     break
                 }
 
-                let index = Range::new(0, cells.len()).ind_sample(&mut rng);
+                let index = Range::new(0, cells.len()).ind_sample(rng);
                 let cell = cells.swap_remove(index);
                 entity_cells.push(cell);
             }
 
-            break KruskalDecorated {
+            return Ok(KruskalDecorated {
                 maze,
                 start_cell,
                 start_opening,
@@ -136,7 +483,551 @@ where
                 end_opening,
                 entity_cells,
                 turret_cells,
+                teleporters: teleporter_pairs,
+                teleport_monsters,
+                shifting_cells: vec![],
+                trigger_cells: vec![],
+                doors: vec![],
+                orbs: vec![],
+                checkpoints: vec![],
+                entities: resolved,
+            })
+        }
+
+        Err(format!(
+            "no room for start ({} times), end ({} times) or teleporters ({} times) in {} attempts with {:?} {:?} maze of size {:?}, {} floors, percent {} and bug {:?}",
+            missing_start,
+            missing_end,
+            missing_teleporter,
+            ::CONFIG.generation_attempts,
+            algorithm,
+            topology,
+            size.iter().collect::<Vec<_>>(),
+            floors,
+            percent,
+            bug.iter().collect::<Vec<_>>(),
+        ))
+    }
+
+    /// Generate a maze, mask it and apply the recipe
+    fn generate_floor<R: Rng>(algorithm: ::maze::Algorithm, topology: ::maze::Topology, size: ::na::VectorN<isize, D>, percent: f64, bug: ::na::VectorN<isize, D>, scale: ::na::Vector3<f32>, recipe: &[::maze::Operation], carvable: &HashSet<::na::VectorN<isize, D>>, rng: &mut R) -> ::maze::Maze<D> {
+        let mut maze = ::maze::Maze::generate(algorithm, topology, size, percent, bug, scale, rng);
+        maze.mask(carvable);
+        for operation in recipe {
+            maze.apply(operation, rng);
+        }
+        maze
+    }
+
+    /// Turrets in turret cells then other entities in entity cells
+    pub fn placed_entities(&self) -> Vec<(&::entity::EntityConf, &::na::VectorN<isize, D>)> {
+        let turrets = self.entities.iter()
+            .filter(|&(e, _)| e.is_turret_like())
+            .flat_map(|(e, &nbr)| {
+                let mut v = vec![];
+                v.resize(nbr, e);
+                v
+            })
+            .zip(self.turret_cells.iter());
+
+        let others = self.entities.iter()
+            .filter(|&(e, _)| !e.is_turret_like() && !e.is_door())
+            .flat_map(|(e, &nbr)| {
+                let mut v = vec![];
+                v.resize(nbr, e);
+                v
+            })
+            .zip(self.entity_cells.iter());
+
+        turrets.chain(others).collect()
+    }
+
+    /// Entities of the level with counts resolved for this maze
+    pub fn entities(&self) -> &BTreeMap<::entity::EntityConf, usize> {
+        &self.entities
+    }
+
+    /// Doors with their door and key cells
+    pub fn placed_doors(&self) -> Vec<(&::entity::EntityConf, &(::na::VectorN<isize, D>, ::na::VectorN<isize, D>))> {
+        self.entities.iter()
+            .filter(|&(e, _)| e.is_door())
+            .flat_map(|(e, &nbr)| {
+                let mut v = vec![];
+                v.resize(nbr, e);
+                v
+            })
+            .zip(self.doors.iter())
+            .collect()
+    }
+
+    /// Cells of start, end, teleporters and their openings, and cells of
+    /// turrets, entities, doors, keys, shifting walls, triggers and objectives
+    fn occupied_cells(&self) -> HashSet<::na::VectorN<isize, D>> {
+        let mut cells = HashSet::new();
+        cells.insert(self.start_cell.clone());
+        cells.insert(self.start_opening.clone());
+        cells.insert(self.end_cell.clone());
+        cells.insert(self.end_opening.clone());
+        for &(ref cell, ref opening) in self.teleporters.iter().flat_map(|pair| pair.iter()) {
+            cells.insert(cell.clone());
+            cells.insert(opening.clone());
+        }
+        for &(ref door, ref key) in &self.doors {
+            cells.insert(door.clone());
+            cells.insert(key.clone());
+        }
+        cells.extend(self.turret_cells.iter().cloned());
+        cells.extend(self.entity_cells.iter().cloned());
+        cells.extend(self.shifting_cells.iter().cloned());
+        cells.extend(self.trigger_cells.iter().cloned());
+        cells.extend(self.orbs.iter().cloned());
+        cells.extend(self.checkpoints.iter().cloned());
+        cells
+    }
+
+    /// Put orbs in free cells of rooms or checkpoints in free cells
+    pub fn place_objective<R: Rng>(&mut self, objective: &Objective, rng: &mut R) -> Result<(), String> {
+        let (count, in_rooms) = match *objective {
+            Objective::Collect(orbs) => (orbs, true),
+            Objective::Checkpoints(checkpoints) => (checkpoints, false),
+            Objective::KillAll | Objective::Erase(_) => return Ok(()),
+        };
+
+        let occupied = self.occupied_cells();
+        let rooms = self.maze.compute_room_zones()
+            .into_iter()
+            .flat_map(|room| room.into_iter())
+            .collect::<HashSet<_>>();
+        let mut cells = self.maze.iterate_maze();
+        cells.retain(|cell| {
+            !self.maze.walls.contains(cell)
+            && !occupied.contains(cell)
+            && (!in_rooms || rooms.contains(cell))
+        });
+        if cells.len() < count {
+            return Err(format!("no room for objective {:?}, only {} free cells", objective, cells.len()));
+        }
+
+        let mut placed = vec![];
+        for _ in 0..count {
+            let index = Range::new(0, cells.len()).ind_sample(rng);
+            placed.push(cells.swap_remove(index));
+        }
+        if in_rooms {
+            self.orbs = placed;
+        } else {
+            self.checkpoints = placed;
+        }
+        Ok(())
+    }
+
+    /// Put doors of the entities on corridor cells of the path from start to end
+    ///
+    /// Keys are put in free cells reachable from start with all doors closed
+    /// so locks can be solved in any order
+    pub fn place_doors<R: Rng>(&mut self, rng: &mut R) -> Result<(), String> {
+        let doors = self.entities.iter()
+            .filter(|&(e, _)| e.is_door())
+            .map(|(_, &nbr)| nbr)
+            .sum::<usize>();
+        if doors == 0 {
+            return Ok(());
+        }
+        if !self.teleporters.is_empty() {
+            return Err("doors can't be put in mazes with teleporters".into());
+        }
+
+        let reserved = self.occupied_cells();
+        let path = self.maze.inner_find_path(self.start_cell.clone(), self.end_cell.clone())
+            .ok_or_else(|| String::from("no path from start to end to put doors on"))?;
+        let mut cells = path.into_iter()
+            .filter(|cell| !reserved.contains(cell) && self.maze.is_corridor(cell))
+            .collect::<Vec<_>>();
+        if cells.len() < doors {
+            return Err(format!("no room for {} doors, only {} corridor cells from start to end", doors, cells.len()));
+        }
+
+        let mut door_cells = vec![];
+        for _ in 0..doors {
+            let index = Range::new(0, cells.len()).ind_sample(rng);
+            door_cells.push(cells.swap_remove(index));
+        }
+
+        // Close all doors while searching keys
+        self.maze.walls.extend(door_cells.iter().cloned());
+        let mut cells = self.maze.iterate_maze();
+        cells.retain(|cell| !self.maze.walls.contains(cell) && !reserved.contains(cell));
+
+        let mut key_cells = vec![];
+        while key_cells.len() < doors && !cells.is_empty() {
+            let index = Range::new(0, cells.len()).ind_sample(rng);
+            let cell = cells.swap_remove(index);
+            if self.maze.inner_find_path(self.start_cell.clone(), cell.clone()).is_some() {
+                key_cells.push(cell);
+            }
+        }
+        for cell in &door_cells {
+            self.maze.walls.remove(cell);
+        }
+
+        if key_cells.len() < doors {
+            return Err(format!("no room for {} keys reachable from start with doors closed", doors));
+        }
+        self.doors = door_cells.into_iter().zip(key_cells).collect();
+        Ok(())
+    }
+
+    /// Choose `triggers` free cells, then inner cells that shift with `percent` probability
+    ///
+    /// Occupied cells never shift
+    pub fn pick_shifting<R: Rng>(&mut self, percent: f64, triggers: usize, rng: &mut R) {
+        let fixed = self.occupied_cells();
+
+        let mut cells = self.maze.iterate_maze();
+        cells.retain(|cell| !fixed.contains(cell) && !self.maze.is_on_border(cell));
+
+        let mut free = cells.iter()
+            .filter(|cell| !self.maze.walls.contains(cell))
+            .cloned()
+            .collect::<Vec<_>>();
+        for _ in 0..triggers {
+            if free.is_empty() {
+                break
+            }
+            let index = Range::new(0, free.len()).ind_sample(rng);
+            self.trigger_cells.push(free.swap_remove(index));
+        }
+
+        let shifting_cells = cells.into_iter()
+            .filter(|cell| !self.trigger_cells.contains(cell) && rng.gen_range(0.0, 100.0) < percent)
+            .collect();
+        self.shifting_cells = shifting_cells;
+    }
+
+    /// Metrics of the maze from start to end
+    pub fn analyse(&self) -> Option<::maze::Analysis> {
+        self.maze.analyse(self.start_cell.clone(), self.end_cell.clone())
+    }
+
+    /// Build doors with their key and objectives, door cells become walls
+    /// of the maze until opened
+    fn create_doors_and_objectives(&mut self, world: &mut ::specs::World) {
+        for (conf, &(ref cell, ref key)) in self.placed_doors() {
+            if let ::entity::EntityConf::Door { trigger } = *conf {
+                ::entity::create_door_w(self.maze.to_world(cell), self.maze.to_world(key), trigger, self.maze.scale, world);
+            }
+        }
+        let door_cells = self.doors.iter().map(|&(ref cell, _)| cell.clone()).collect::<Vec<_>>();
+        self.maze.walls.extend(door_cells);
+
+        for cell in &self.orbs {
+            ::entity::create_orb_w(self.maze.to_world(cell), self.maze.scale, world);
+        }
+        for (index, cell) in self.checkpoints.iter().enumerate() {
+            ::entity::create_checkpoint_w(self.maze.to_world(cell), self.maze.scale, index, world);
+        }
+    }
+}
+
+// FIXME: factorize
+impl KruskalDecorated<::na::U2> {
+    /// Build walls, teleport, player and put entities in their cells
+    pub fn create(mut self, world: &mut ::specs::World) {
+        // Build walls
+        let mut maze_colors = HashMap::new();
+        maze_colors.insert(self.start_cell, (::CONFIG.start_color, false));
+        maze_colors.insert(self.end_cell, (::CONFIG.end_color, true));
+        for &(ref cell, _) in self.teleporters.iter().flat_map(|pair| pair.iter()) {
+            maze_colors.insert(cell.clone(), (::CONFIG.teleporter_color, false));
+        }
+        for cell in &self.trigger_cells {
+            maze_colors.insert(cell.clone(), (::CONFIG.shift_trigger_color, false));
+        }
+
+        // Shifting walls are built apart
+        let closed = self.shifting_cells.iter()
+            .filter(|cell| self.maze.walls.contains(cell))
+            .cloned()
+            .collect::<Vec<_>>();
+        for cell in &closed {
+            self.maze.walls.remove(cell);
+        }
+        match self.maze.topology {
+            ::maze::Topology::Square => ::entity::create_2d_maze_walls_w(&maze_colors, &self.maze, world),
+            ::maze::Topology::Hexagonal => ::entity::create_hexagonal_maze_walls_w(&maze_colors, &self.maze, world),
+        }
+        self.maze.walls.extend(closed);
+        ::entity::create_shifting_walls_w(&self.shifting_cells, &self.maze, world);
+
+        for cell in &self.trigger_cells {
+            ::entity::create_shift_trigger_w(self.maze.to_world(cell), self.maze.scale, world);
+        }
+
+        // Build teleport
+        let end_dir = self.maze.direction(&self.end_cell, &self.end_opening);
+        ::entity::create_teleport_w(
+            ::na::Isometry3::from_parts(
+                ::na::Translation3::from_vector(self.maze.to_world(&self.end_cell)),
+                ::na::UnitQuaternion::rotation_between(&::na::Vector3::z(), &end_dir)
+                    .unwrap_or(::na::UnitQuaternion::new(::na::Vector3::x() * ::std::f32::consts::PI)),
+            ),
+            self.maze.scale,
+            ::component::Teleport::Action(::resource::LevelAction::Next),
+            false,
+            world,
+        );
+
+        // Build teleporters, each one moves to the opening of the other one
+        for pair in &self.teleporters {
+            for (&(ref cell, ref opening), &(_, ref destination)) in pair.iter().zip(pair.iter().rev()) {
+                let dir = self.maze.direction(cell, opening);
+                ::entity::create_teleport_w(
+                    ::na::Isometry3::from_parts(
+                        ::na::Translation3::from_vector(self.maze.to_world(cell)),
+                        ::na::UnitQuaternion::rotation_between(&::na::Vector3::z(), &dir)
+                            .unwrap_or(::na::UnitQuaternion::new(::na::Vector3::x() * ::std::f32::consts::PI)),
+                    ),
+                    self.maze.scale,
+                    ::component::Teleport::Destination(self.maze.to_world(destination)),
+                    self.teleport_monsters,
+                    world,
+                );
+            }
+        }
+
+        // Build player
+        let dir = self.maze.direction(&self.start_cell, &self.start_opening);
+        let player_pos = self.maze.to_world(&self.start_cell)
+            - 0.2 * ::na::Vector3::new(dir[0] * self.maze.scale[0], dir[1] * self.maze.scale[1], 0.0);
+        world.write_resource::<::resource::PlayerControl>().pointer =
+            [(-dir[1]).atan2(dir[0]), 0.0];
+        ::entity::create_player_w(player_pos, false, world);
+
+        // Build turrets and entities
+        for (conf, cell) in self.placed_entities() {
+            let pos = self.maze.to_world(cell);
+            conf.create(pos, world);
+        }
+
+        self.create_doors_and_objectives(world);
+
+        // Build maze resource
+        world.add_resource(::resource::Maze::Maze2D(self.maze));
+    }
+}
+
+impl KruskalDecorated<::na::U3> {
+    /// Build walls, teleport, player and put entities in their cells
+    pub fn create(mut self, world: &mut ::specs::World) {
+        // Build walls
+        let mut maze_colors = HashMap::new();
+        maze_colors.insert(self.start_cell, (::CONFIG.start_color, false));
+        maze_colors.insert(self.end_cell, (::CONFIG.end_color, true));
+        for &(ref cell, _) in self.teleporters.iter().flat_map(|pair| pair.iter()) {
+            maze_colors.insert(cell.clone(), (::CONFIG.teleporter_color, false));
+        }
+
+        match self.maze.topology {
+            ::maze::Topology::Square => ::entity::create_3d_maze_walls_w(&maze_colors, &self.maze, world),
+            ::maze::Topology::Hexagonal => ::entity::create_hexagonal_prism_maze_walls_w(&maze_colors, &self.maze, world),
+        }
+
+        // Build teleport
+        let end_dir = self.maze.direction(&self.end_cell, &self.end_opening);
+        ::entity::create_teleport_w(
+            ::na::Isometry3::from_parts(
+                ::na::Translation3::from_vector(self.maze.to_world(&self.end_cell)),
+                ::na::UnitQuaternion::rotation_between(&::na::Vector3::z(), &end_dir)
+                    .unwrap_or(::na::UnitQuaternion::new(::na::Vector3::x() * ::std::f32::consts::PI)),
+            ),
+            self.maze.scale,
+            ::component::Teleport::Action(::resource::LevelAction::Next),
+            false,
+            world,
+        );
+
+        // Build teleporters, each one moves to the opening of the other one
+        for pair in &self.teleporters {
+            for (&(ref cell, ref opening), &(_, ref destination)) in pair.iter().zip(pair.iter().rev()) {
+                let dir = self.maze.direction(cell, opening);
+                ::entity::create_teleport_w(
+                    ::na::Isometry3::from_parts(
+                        ::na::Translation3::from_vector(self.maze.to_world(cell)),
+                        ::na::UnitQuaternion::rotation_between(&::na::Vector3::z(), &dir)
+                            .unwrap_or(::na::UnitQuaternion::new(::na::Vector3::x() * ::std::f32::consts::PI)),
+                    ),
+                    self.maze.scale,
+                    ::component::Teleport::Destination(self.maze.to_world(destination)),
+                    self.teleport_monsters,
+                    world,
+                );
             }
         }
+
+        // Build player
+        let dir = self.maze.direction(&self.start_cell, &self.start_opening);
+        let player_pos = self.maze.to_world(&self.start_cell)
+            - 0.2 * ::na::Vector3::new(dir[0] * self.maze.scale[0], dir[1] * self.maze.scale[1], 0.0);
+        world.write_resource::<::resource::PlayerControl>().pointer =
+            [(-dir[1]).atan2(dir[0]), 0.0];
+        ::entity::create_player_w(player_pos, true, world);
+
+        // Build turrets and entities
+        for (conf, cell) in self.placed_entities() {
+            let pos = self.maze.to_world(cell);
+            conf.create(pos, world);
+        }
+
+        self.create_doors_and_objectives(world);
+
+        // Build maze resource
+        world.add_resource(::resource::Maze::Maze3D(self.maze));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_maze() -> ::maze::Maze<::na::U2> {
+        ::maze::Maze::new_rectangle(::na::Vector2::new(10, 10), ::na::Vector3::new(1.0, 1.0, 1.0))
+    }
+
+    fn density(density: f64, pool: Vec<(::entity::EntityConf, usize)>, min: usize, max: Option<usize>) -> Density {
+        Density {
+            density,
+            per_room: false,
+            pool,
+            min,
+            max,
+        }
+    }
+
+    #[test]
+    fn density_resolve_clamps_count() {
+        let turret = ::entity::EntityConf::Turret;
+        let maze = open_maze();
+        let mut rng = seeded_rng(0);
+
+        // 100 free cells
+        let mut entities = BTreeMap::new();
+        density(0.1, vec![(turret.clone(), 1)], 0, None).resolve(&maze, &mut entities, &mut rng);
+        assert_eq!(entities[&turret], 10);
+
+        let mut entities = BTreeMap::new();
+        density(0.01, vec![(turret.clone(), 1)], 5, None).resolve(&maze, &mut entities, &mut rng);
+        assert_eq!(entities[&turret], 5);
+
+        let mut entities = BTreeMap::new();
+        density(0.5, vec![(turret.clone(), 1)], 0, Some(3)).resolve(&maze, &mut entities, &mut rng);
+        assert_eq!(entities[&turret], 3);
+
+        // Entities already there are kept
+        let mut entities = BTreeMap::new();
+        entities.insert(turret.clone(), 2);
+        density(0.0, vec![(turret.clone(), 1)], 0, None).resolve(&maze, &mut entities, &mut rng);
+        assert_eq!(entities[&turret], 2);
+    }
+
+    #[test]
+    fn density_resolve_draws_by_weight() {
+        let turret = ::entity::EntityConf::Turret;
+        let bouncer = ::entity::EntityConf::Bouncer { eraser: false };
+        let avoider = ::entity::EntityConf::Avoider { eraser: false };
+        let maze = open_maze();
+        let mut rng = seeded_rng(0);
+
+        let mut entities = BTreeMap::new();
+        density(
+            10.0,
+            vec![(turret.clone(), 1), (bouncer.clone(), 3), (avoider.clone(), 0)],
+            0,
+            None,
+        ).resolve(&maze, &mut entities, &mut rng);
+
+        let turrets = entities[&turret];
+        let bouncers = entities[&bouncer];
+        assert_eq!(turrets + bouncers, 1000);
+        assert!(!entities.contains_key(&avoider));
+        assert!(turrets > 150 && turrets < 350, "{} turrets out of 1000", turrets);
+    }
+
+    type Cells = Vec<Vec<isize>>;
+
+    /// Walls, start and end cells, turret cells and entity cells
+    fn layout<D>(maze: &KruskalDecorated<D>) -> (Cells, Cells, Cells, Cells)
+    where
+        D: ::na::Dim + ::na::DimName + Hash,
+        D::Value: Mul<typenum::UInt<typenum::UTerm, typenum::B1>, Output = D::Value>
+            + ::generic_array::ArrayLength<isize> + ::generic_array::ArrayLength<f32>,
+    {
+        let cells = |cells: Vec<&::na::VectorN<isize, D>>| {
+            cells.iter().map(|c| c.iter().cloned().collect()).collect::<Cells>()
+        };
+        let mut walls = cells(maze.maze.walls.iter().collect());
+        walls.sort();
+        (
+            walls,
+            cells(vec![&maze.start_cell, &maze.end_cell]),
+            cells(maze.turret_cells.iter().collect()),
+            cells(maze.entity_cells.iter().collect()),
+        )
+    }
+
+    fn generated_layout(level: &Level, seed: u64) -> (Cells, Cells, Cells, Cells) {
+        match level.generate(seed).unwrap() {
+            Generated::Maze2D(maze) => layout(&maze),
+            Generated::Maze3D(maze) => layout(&maze),
+        }
+    }
+
+    fn assert_reproducible(level: &Level) {
+        assert_eq!(generated_layout(level, 7), generated_layout(level, 7));
+        assert!(generated_layout(level, 7) != generated_layout(level, 8));
+    }
+
+    fn kruskal_entities() -> BTreeMap<::entity::EntityConf, usize> {
+        let mut entities = BTreeMap::new();
+        entities.insert(::entity::EntityConf::Turret, 2);
+        entities.insert(::entity::EntityConf::Bouncer { eraser: false }, 3);
+        entities
+    }
+
+    #[test]
+    fn kill_all_kruskal_2d_is_reproducible() {
+        assert_reproducible(&Level::KillAllKruskal2D(kill_all_kruskal::Conf2D {
+            algorithm: ::maze::Algorithm::Kruskal,
+            topology: ::maze::Topology::Square,
+            mask: ::mask::Mask::Full,
+            wrap: None,
+            recipe: None,
+            floors: 1,
+            teleporters: 0,
+            teleport_monsters: false,
+            size: (15, 15),
+            scale: (1.0, 1.0, 1.0),
+            percent: 20.0,
+            bug: (1, 1),
+            entities: kruskal_entities(),
+            densities: vec![],
+            objective: Objective::KillAll,
+        }));
+    }
+
+    #[test]
+    fn kill_all_kruskal_3d_is_reproducible() {
+        assert_reproducible(&Level::KillAllKruskal3D(kill_all_kruskal::Conf3D {
+            algorithm: ::maze::Algorithm::Kruskal,
+            topology: ::maze::Topology::Square,
+            mask: ::mask::Mask::Full,
+            recipe: None,
+            size: (9, 9, 9),
+            scale: (1.0, 1.0, 1.0),
+            percent: 20.0,
+            bug: (1, 1, 1),
+            entities: kruskal_entities(),
+            densities: vec![],
+            objective: Objective::KillAll,
+        }));
     }
 }
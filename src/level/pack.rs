@@ -0,0 +1,77 @@
+//! Level packs loaded from the `packs` directory of the user data directory
+//!
+//! A pack is a RON file holding a `Pack`, each pack has its own hall and scores.
+
+use app_dirs2::{app_root, AppDataType};
+use std::ffi::OsStr;
+use std::fs::{self, File};
+
+/// Index of the campaign of `CONFIG.levels` in `PACKS`
+pub const CAMPAIGN: usize = 0;
+
+const DIRECTORY: &str = "packs";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Pack {
+    /// Scores of the pack are saved under its name
+    pub name: String,
+    pub levels: Vec<Vec<::level::Level>>,
+}
+
+lazy_static! {
+    static ref LOADED: (Vec<Pack>, Vec<String>) = load();
+    /// Campaign followed by packs in file name order
+    pub static ref PACKS: &'static [Pack] = &LOADED.0;
+    /// Why packs were ignored, reported by the binaries
+    pub static ref PACK_ERRORS: &'static [String] = &LOADED.1;
+}
+
+fn load() -> (Vec<Pack>, Vec<String>) {
+    let mut errors = vec![];
+    let mut packs = vec![Pack {
+        name: "campaign".into(),
+        levels: ::CONFIG.levels.clone(),
+    }];
+    for pack in load_packs(&mut errors) {
+        if packs.iter().any(|p| p.name == pack.name) {
+            errors.push(format!("Ignored level pack \"{}\": name already used", pack.name));
+            continue;
+        }
+        packs.push(pack);
+    }
+    (packs, errors)
+}
+
+/// Packs of the directory, those failing to load are ignored and their error pushed
+fn load_packs(errors: &mut Vec<String>) -> Vec<Pack> {
+    let directory = match app_root(AppDataType::UserData, &::resource::APP_INFO) {
+        Ok(mut path) => {
+            path.push(DIRECTORY);
+            path
+        }
+        Err(e) => {
+            errors.push(format!("Failed to get level pack directory: {}", e));
+            return vec![];
+        }
+    };
+
+    // No directory means no pack
+    let mut paths = match fs::read_dir(&directory) {
+        Ok(read_dir) => read_dir
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension() == Some(OsStr::new("ron")))
+            .collect::<Vec<_>>(),
+        Err(_) => return vec![],
+    };
+    paths.sort();
+
+    paths.iter()
+        .filter_map(|path| {
+            File::open(path)
+                .map_err(|e| e.to_string())
+                .and_then(|file| ::ron::de::from_reader(file).map_err(|e| e.to_string()))
+                .map_err(|e| errors.push(format!("Failed to load level pack {}: {}", path.display(), e)))
+                .ok()
+        })
+        .collect()
+}
@@ -0,0 +1,45 @@
+//! 2D mazes whose walls open and close during play
+
+use rand::Rng;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Conf {
+    /// Maze before any shift
+    pub maze: ::level::kill_all_kruskal::Conf2D,
+    /// Percent of inner cells that can open or close
+    pub shifting: f64,
+    /// Seconds between two shifts, none if only triggers shift the walls
+    #[serde(default)]
+    pub period: Option<f32>,
+    /// Number of walls toggled by a shift
+    pub shifts: usize,
+    /// Number of cells shifting the walls when the player enters them
+    #[serde(default)]
+    pub triggers: usize,
+}
+
+impl Conf {
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> Result<::level::KruskalDecorated<::na::U2>, String> {
+        if self.maze.topology != ::maze::Topology::Square {
+            return Err("shifting walls require square topology".into());
+        }
+        let mut maze = self.maze.generate(rng)?;
+        // Doors are walls the connectivity check of shifts doesn't know
+        if maze.entities().keys().any(|e| e.is_door()) {
+            return Err("doors can't be in mazes with shifting walls".into());
+        }
+        maze.pick_shifting(self.shifting, self.triggers, rng);
+        Ok(maze)
+    }
+
+    /// Resource shifting the walls of the maze created from `maze`
+    pub fn shifting_walls(&self, maze: &::level::KruskalDecorated<::na::U2>, seed: u64) -> ::resource::ShiftingWalls {
+        ::resource::ShiftingWalls {
+            period: self.period,
+            timer: 0.0,
+            shifts: self.shifts,
+            end: maze.end_cell,
+            rng: ::level::seeded_rng(seed),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! 2D mazes where monsters come in timed waves
+
+use rand::Rng;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Wave {
+    /// Seconds before the wave comes after the previous one, it comes earlier
+    /// if all monsters are killed
+    pub delay: f32,
+    pub entities: BTreeMap<::entity::EntityConf, usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Conf {
+    /// Maze with the entities there from the start
+    pub maze: ::level::kill_all_kruskal::Conf2D,
+    pub waves: Vec<Wave>,
+}
+
+impl Conf {
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> Result<::level::KruskalDecorated<::na::U2>, String> {
+        if self.waves.iter().any(|wave| wave.entities.keys().any(|e| e.is_door())) {
+            return Err("doors can't come in waves".into());
+        }
+        let maze = self.maze.generate(rng)?;
+        if wave_cells(&maze).is_empty() && self.waves.iter().any(|wave| wave.entities.values().any(|&nbr| nbr != 0)) {
+            return Err("no free cell far enough from start for waves".into());
+        }
+        Ok(maze)
+    }
+
+    /// Resource creating the waves in free cells away from start
+    pub fn waves(&self, maze: &::level::KruskalDecorated<::na::U2>, seed: u64) -> ::resource::Waves {
+        let mut rng = ::level::seeded_rng(seed);
+        let cells = wave_cells(maze);
+
+        let waves = self.waves.iter()
+            .map(|wave| {
+                let mut entities = vec![];
+                for (conf, &nbr) in &wave.entities {
+                    for _ in 0..nbr {
+                        entities.push(conf.clone());
+                    }
+                }
+                let mut cells = cells.clone();
+                rng.shuffle(&mut cells);
+                (wave.delay, entities, cells)
+            })
+            .collect();
+
+        ::resource::Waves {
+            waves,
+            next: 0,
+            timer: 0.0,
+            coming: false,
+        }
+    }
+}
+
+/// Free cells away from start where waves come
+fn wave_cells(maze: &::level::KruskalDecorated<::na::U2>) -> Vec<::na::Vector2<isize>> {
+    let mut cells = maze.maze.iterate_maze();
+    cells.retain(|cell| {
+        !maze.maze.walls.contains(cell)
+        && (maze.start_cell.clone() - cell.clone()).iter().fold(0, |acc, c| acc + c.pow(2)) > 5_isize.pow(2)
+        && *cell != maze.end_cell
+        && *cell != maze.end_opening
+    });
+    cells
+}
+
+/// Create the entities of the wave set as coming by WaveSystem
+///
+/// Entities are put on cells the player doesn't touch, one per cell while
+/// there are enough, on any cell of the wave if the player touches them all
+pub fn create_coming_wave(world: &mut ::specs::World) {
+    let player_cells = ::level::player_cells(world);
+    let entities = {
+        let mut waves = world.write_resource::<::resource::Waves>();
+        if !waves.coming {
+            return;
+        }
+        let entities = {
+            let (_, ref entities, ref cells) = waves.waves[waves.next];
+            let mut free = cells.iter()
+                .filter(|cell| !player_cells.contains(cell))
+                .cloned()
+                .collect::<Vec<_>>();
+            if free.is_empty() {
+                free = cells.clone();
+            }
+            entities.iter()
+                .cloned()
+                .zip(free.into_iter().cycle())
+                .collect::<Vec<_>>()
+        };
+        waves.coming = false;
+        waves.timer = 0.0;
+        waves.next += 1;
+        entities
+    };
+    for (conf, cell) in entities {
+        let pos = match *world.read_resource::<::resource::Maze>() {
+            ::resource::Maze::Maze2D(ref maze) => maze.to_world(&cell),
+            ::resource::Maze::Maze3D(_) => unreachable!("waves are in 2D mazes"),
+        };
+        conf.create(pos, world);
+    }
+}
@@ -0,0 +1,48 @@
+extern crate alga;
+extern crate fps_counter;
+extern crate generic_array;
+#[macro_use]
+extern crate imgui;
+extern crate imgui_sys;
+extern crate clipboard;
+#[macro_use]
+extern crate lazy_static;
+extern crate nalgebra as na;
+extern crate ncollide;
+extern crate nphysics3d as nphysics;
+extern crate pathfinding;
+extern crate png;
+extern crate rand;
+extern crate ron;
+#[macro_use]
+extern crate serde_derive;
+extern crate specs;
+extern crate typenum;
+#[macro_use]
+extern crate vulkano;
+#[macro_use]
+extern crate vulkano_shader_derive;
+extern crate wavefront_obj;
+extern crate winit;
+extern crate app_dirs2;
+extern crate rodio;
+extern crate show_message;
+extern crate palette;
+extern crate locale_config;
+extern crate fluent_locale;
+
+#[macro_use]
+pub mod util;
+pub mod graphics;
+pub mod audio;
+pub mod entity;
+pub mod component;
+pub mod system;
+pub mod resource;
+pub mod maze;
+pub mod mask;
+pub mod config;
+pub mod level;
+pub mod copy_paste;
+
+pub use config::CONFIG;
@@ -1,48 +1,15 @@
-extern crate alga;
-extern crate fps_counter;
-extern crate generic_array;
 #[macro_use]
+extern crate hyperzen_training;
+extern crate fps_counter;
 extern crate imgui;
-#[macro_use]
-extern crate lazy_static;
-extern crate nalgebra as na;
-extern crate ncollide;
-extern crate nphysics3d as nphysics;
-extern crate pathfinding;
-extern crate png;
-extern crate rand;
-extern crate ron;
-#[macro_use]
-extern crate serde_derive;
 extern crate specs;
-extern crate typenum;
 #[macro_use]
 extern crate vulkano;
-#[macro_use]
-extern crate vulkano_shader_derive;
 extern crate vulkano_win;
-extern crate wavefront_obj;
 extern crate winit;
-extern crate app_dirs2;
-extern crate rodio;
 extern crate show_message;
-extern crate palette;
-extern crate locale_config;
-extern crate fluent_locale;
 
-#[macro_use]
-mod util;
-mod graphics;
-mod audio;
-mod entity;
-mod component;
-mod system;
-mod resource;
-pub mod maze;
-mod config;
-mod level;
-
-pub use config::CONFIG;
+use hyperzen_training::{component, graphics, level, resource, system, util, CONFIG};
 
 use vulkano_win::VkSurfaceBuild;
 use show_message::UnwrapOrShow;
@@ -89,6 +56,7 @@ fn init_imgui() -> ::imgui::ImGui {
     imgui.set_imgui_key(::imgui::ImGuiKey::Y, 17);
     imgui.set_imgui_key(::imgui::ImGuiKey::Z, 18);
     CONFIG.style.set_style(imgui.style_mut());
+    ::hyperzen_training::copy_paste::connect_imgui();
     imgui
 }
 
@@ -156,6 +124,16 @@ fn new_game() -> ControlFlow {
 
     let debug = ::std::env::var("HYPERZEN_TRAINING_DEBUG").map(|v| v == "1").unwrap_or(false);
 
+    if !::level::pack::PACK_ERRORS.is_empty() {
+        ::show_message::show(::level::pack::PACK_ERRORS.join("\n"));
+    }
+
+    if debug {
+        if let Err(e) = ::level::validate_levels(0) {
+            ::show_message::show(format!("Invalid level: {}", e));
+        }
+    }
+
     let mut world = specs::World::new();
     world.register::<::component::Player>();
     world.register::<::component::Teleport>();
@@ -186,6 +164,13 @@ fn new_game() -> ControlFlow {
     world.register::<::component::Proximitor>();
     world.register::<::component::FollowPlayer>();
     world.register::<::component::PhysicSensor>();
+    world.register::<::component::ShiftingWall>();
+    world.register::<::component::ShiftTrigger>();
+    world.register::<::component::Door>();
+    world.register::<::component::DoorSwitch>();
+    world.register::<::component::Orb>();
+    world.register::<::component::Checkpoint>();
+    world.register::<::component::RoomSensor>();
     world.add_resource(::resource::Help(String::new()));
     world.add_resource(text);
     world.add_resource(graphics.clone());
@@ -203,6 +188,9 @@ fn new_game() -> ControlFlow {
     world.add_resource(::resource::Audio::init(&save));
     world.add_resource(::resource::LevelActions(vec![]));
     world.add_resource(::resource::ErasedStatus::new());
+    world.add_resource(::resource::Waves::new());
+    world.add_resource(::resource::Encounters(vec![]));
+    world.add_resource(::resource::Objective(::level::Objective::KillAll));
     let menu_state = ::resource::MenuState::new(&save);
     world.add_resource(save);
     world.add_resource(menu_state);
@@ -221,24 +209,31 @@ fn new_game() -> ControlFlow {
         .add(::system::AudioSystem, "audio", &[])
         .add(::system::MenuGameControlSystem, "menu_game", &[])
         .add(::system::PlayerControlSystem, "player_control", &[])
-        .add(::system::AvoiderControlSystem, "avoider_control", &[])
+        .add(::system::ShiftingSystem, "shifting", &[])
+        .add(::system::DistanceFieldSystem, "distance_field", &["shifting"])
+        .add(::system::AvoiderControlSystem, "avoider_control", &["distance_field"])
         .add(::system::BouncerControlSystem, "bouncer_control", &[])
         .add(::system::TeleportSystem, "teleport", &[])
         .add(::system::FollowPlayerSystem, "follower_control", &[])
         .add(::system::TurretControlSystem::new(), "turret_control", &[])
         .add(::system::GeneratorSystem, "generator", &[])
+        .add(::system::WaveSystem, "wave", &[])
+        .add(::system::EncounterSystem, "encounter", &[])
         .add(::system::ShootSystem::new(), "shoot", &[])
         .add(::system::HookSystem::new(), "hook", &[])
         .add(::system::PhysicSystem, "physic", &[])
+        .add(::system::WrapSystem, "wrap", &["physic"])
         .add(::system::DeleterSystem, "deleter", &[])
         .add(::system::PlayerDeathSystem, "death", &[])
-        .add(::system::ActivateSystem, "activate", &[])
+        .add(::system::ObjectiveSystem, "objective", &[])
+        .add(::system::ActivateSystem, "activate", &["objective"])
         .add(::system::ReducerSystem, "reducer", &[])
         .add(::system::DepthCoefSystem, "depth_coef", &[])
         .add(::system::DepthBallSystem, "depth_ball", &[])
         .add(::system::AttractedSystem::new(), "attracted", &[])
         .add_barrier() // following systems will delete physic bodies
         .add(::system::LifeSystem, "life", &[])
+        .add(::system::DoorSystem, "door", &["life"])
         .build();
 
     let mut prepare_game_draw_dispatcher = ::specs::DispatcherBuilder::new()
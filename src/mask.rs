@@ -0,0 +1,115 @@
+//! Masks of the cells that can be carved when generating a maze
+//!
+//! Masks are defined on the x and y axes, 3D mazes use the same mask for every layer.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::hash::Hash;
+use std::ops::Mul;
+use typenum;
+
+const DIRECTORY: &str = "assets/masks";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum Mask {
+    Full,
+    /// Disk inscribed in the maze
+    Circle,
+    /// Circle without a disk of `inner` ratio of its radius
+    Ring { inner: f64 },
+    /// Two bands crossing at the center, `width` is the ratio of the half maze
+    Cross { width: f64 },
+    /// PNG file in `assets/masks` stretched over the maze, bright opaque pixels can be carved,
+    /// not available in packed builds
+    Image(String),
+}
+
+impl Default for Mask {
+    fn default() -> Self {
+        Mask::Full
+    }
+}
+
+impl Mask {
+    /// Cells of a maze of the given size that can be carved
+    pub fn carvable<D>(&self, size: &::na::VectorN<isize, D>) -> Result<HashSet<::na::VectorN<isize, D>>, String>
+    where
+        D: ::na::Dim + ::na::DimName + Hash,
+        D::Value: Mul<typenum::UInt<typenum::UTerm, typenum::B1>, Output = D::Value>
+            + ::generic_array::ArrayLength<isize>,
+    {
+        let contains: Box<Fn(f64, f64) -> bool> = match *self {
+            Mask::Full => Box::new(|_, _| true),
+            Mask::Circle => Box::new(|u, v| u.powi(2) + v.powi(2) <= 1.0),
+            Mask::Ring { inner } => Box::new(move |u, v| {
+                let d = u.powi(2) + v.powi(2);
+                d <= 1.0 && d >= inner.powi(2)
+            }),
+            Mask::Cross { width } => Box::new(move |u, v| u.abs() <= width || v.abs() <= width),
+            Mask::Image(ref file) => {
+                let (width, height, bright) = load_image(file)?;
+                Box::new(move |u, v| {
+                    let x = (((u + 1.0) / 2.0 * width as f64) as usize).min(width - 1);
+                    let y = (((v + 1.0) / 2.0 * height as f64) as usize).min(height - 1);
+                    bright[y * width + x]
+                })
+            }
+        };
+
+        Ok(::maze::Maze::<D>::iterate_area(size)
+            .into_iter()
+            .filter(|cell| {
+                let u = (cell[0] as f64 + 0.5) / size[0] as f64 * 2.0 - 1.0;
+                // Image rows are maze rows as in the ascii format
+                let v = (cell[1] as f64 + 0.5) / size[1] as f64 * 2.0 - 1.0;
+                contains(u, v)
+            })
+            .collect())
+    }
+}
+
+/// Return width, height and whereas each pixel is bright and opaque
+fn load_image(file: &str) -> Result<(usize, usize, Vec<bool>), String> {
+    // No mask is embedded in the executable
+    if cfg!(feature = "packed") {
+        return Err(format!("Mask \"{}\" can't be loaded: image masks aren't available in packed builds", file));
+    }
+    let path = format!("{}/{}", DIRECTORY, file);
+    let file = File::open(&path).map_err(|e| format!("Failed to open \"{}\": {}", path, e))?;
+    let (info, mut reader) = ::png::Decoder::new(file)
+        .read_info()
+        .map_err(|e| format!("Failed to decode \"{}\": {}", path, e))?;
+
+    if info.bit_depth != ::png::BitDepth::Eight {
+        return Err(format!("Mask \"{}\" must have 8 bits depth", path));
+    }
+    let channels = match info.color_type {
+        ::png::ColorType::Grayscale => 1,
+        ::png::ColorType::GrayscaleAlpha => 2,
+        ::png::ColorType::RGB => 3,
+        ::png::ColorType::RGBA => 4,
+        ::png::ColorType::Indexed => {
+            return Err(format!("Mask \"{}\" must not be indexed", path))
+        }
+    };
+
+    let mut buf = vec![0; info.buffer_size()];
+    reader
+        .next_frame(&mut buf)
+        .map_err(|e| format!("Failed to decode \"{}\": {}", path, e))?;
+
+    let bright = buf.chunks(channels)
+        .map(|pixel| {
+            let (color, alpha) = match channels {
+                1 => (&pixel[..1], 255),
+                2 => (&pixel[..1], pixel[1]),
+                3 => (&pixel[..3], 255),
+                _ => (&pixel[..3], pixel[3]),
+            };
+            let luminance = color.iter().map(|&c| c as usize).sum::<usize>() / color.len();
+            luminance >= 128 && alpha >= 128
+        })
+        .collect();
+
+    Ok((info.width as usize, info.height as usize, bright))
+}
@@ -1,6 +1,9 @@
 use rand::distributions::{IndependentSample, Range};
+use rand::Rng;
 use std::collections::HashSet;
 use std::collections::HashMap;
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
 use std::hash::Hash;
 use util::Pop;
 use std::ops::Mul;
@@ -17,6 +20,17 @@ where
     pub cost: isize,
 }
 
+/// Cost of the shortest path from each reachable cell to the goal
+pub struct DistanceField<D>
+where
+    D: ::na::Dim + ::na::DimName,
+    D::Value: Mul<typenum::UInt<typenum::UTerm, typenum::B1>, Output = D::Value>
+        + ::generic_array::ArrayLength<isize>,
+{
+    pub goal: ::na::VectorN<isize, D>,
+    pub distances: HashMap<::na::VectorN<isize, D>, isize>,
+}
+
 pub struct Maze<D>
 where
     D: ::na::Dim + ::na::DimName,
@@ -27,7 +41,11 @@ where
     pub size: ::na::VectorN<isize, D>,
     pub openings: Vec<Opening<D>>,
     pub neighbours: Vec<::na::VectorN<isize, D>>,
-    pub scale: f32,
+    pub topology: Topology,
+    /// Opposite borders of x and y axes are connected
+    pub wrap: bool,
+    /// Size of cells in the world on each axis, z is the height of 2D mazes
+    pub scale: ::na::Vector3<f32>,
 }
 
 #[allow(unused)]
@@ -40,14 +58,14 @@ where
     pub fn is_neighbouring_corridor(&self, cell: &::na::VectorN<isize, D>) -> bool {
         self.neighbours
             .iter()
-            .map(|n| n + cell)
+            .map(|n| self.wrapped(n + cell))
             .any(|n| self.is_corridor(&n))
     }
 
     pub fn is_neighbouring_wall(&self, cell: &::na::VectorN<isize, D>) -> bool {
         self.neighbours
             .iter()
-            .map(|n| n + cell)
+            .map(|n| self.wrapped(n + cell))
             .any(|n| self.walls.contains(&n))
     }
 
@@ -59,28 +77,47 @@ where
                     opening
                         .requires
                         .iter()
-                        .all(|o| !self.walls.contains(&(cell.clone() + o)))
+                        .all(|o| !self.is_wall(&(cell.clone() + o)))
                 })
                 .count() <= 2
     }
 
-    pub fn new_empty(scale: f32) -> Self {
+    pub fn new_empty(scale: ::na::Vector3<f32>) -> Self {
         Maze {
             scale,
             walls: HashSet::new(),
             size: ::na::zero(),
             openings: Self::openings(),
             neighbours: Self::neighbours(),
+            topology: Topology::Square,
+            wrap: false,
         }
     }
 
-    pub fn new_rectangle(size: ::na::VectorN<isize, D>, scale: f32) -> Self {
+    pub fn new_rectangle(size: ::na::VectorN<isize, D>, scale: ::na::Vector3<f32>) -> Self {
         Maze {
             scale,
             walls: HashSet::new(),
             size: size,
             openings: Self::openings(),
             neighbours: Self::neighbours(),
+            topology: Topology::Square,
+            wrap: false,
+        }
+    }
+
+    /// Change the adjacency of cells, walls are kept
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+        match topology {
+            Topology::Square => {
+                self.neighbours = Self::neighbours();
+                self.openings = Self::openings();
+            }
+            Topology::Hexagonal => {
+                self.neighbours = Self::hexagonal_neighbours();
+                self.openings = Self::hexagonal_openings();
+            }
         }
     }
 
@@ -130,6 +167,21 @@ where
         self.size += dl * 2;
     }
 
+    /// Put another maze after this one along x
+    ///
+    /// Both mazes must be circled so they don't connect
+    pub fn append_floor(&mut self, floor: Self) {
+        let mut dl = ::na::VectorN::<isize, D>::from_iterator((0..1).cycle());
+        dl[0] = self.size[0];
+        for wall in floor.walls {
+            self.walls.insert(wall + dl.clone());
+        }
+        self.size[0] += floor.size[0];
+        for i in 1..D::dim() {
+            self.size[i] = self.size[i].max(floor.size[i]);
+        }
+    }
+
     pub fn iterate_maze(&self) -> Vec<::na::VectorN<isize, D>> {
         Self::iterate_area(&self.size)
     }
@@ -156,9 +208,13 @@ where
     }
 
     /// Create a wall that circle the maze
+    /// Wrapped axes have no border
     pub fn circle(&mut self) {
         for cell in self.iterate_maze() {
             for i in 0..D::dim() {
+                if self.is_wrapped_axis(i) {
+                    continue;
+                }
                 if cell[i] == 0 || cell[i] == self.size[i] - 1 {
                     self.walls.insert(cell.clone());
                 }
@@ -166,6 +222,15 @@ where
         }
     }
 
+    /// Build walls on all cells that can't be carved
+    pub fn mask(&mut self, carvable: &HashSet<::na::VectorN<isize, D>>) {
+        for cell in self.iterate_maze() {
+            if !carvable.contains(&cell) {
+                self.walls.insert(cell);
+            }
+        }
+    }
+
     /// Filter(openings) -> if we keep the cell
     pub fn compute_zones<F>(&self, filter: F) -> Vec<HashSet<::na::VectorN<isize, D>>>
     where
@@ -179,7 +244,11 @@ where
         let mut to_visit = HashSet::new();
         let mut zones = Vec::new();
 
-        while let Some(cell) = unvisited.pop() {
+        // Iterate in maze order so zones are always returned in the same order
+        for cell in self.iterate_maze() {
+            if !unvisited.remove(&cell) {
+                continue;
+            }
             let mut zone = HashSet::new();
             to_visit.insert(cell);
 
@@ -188,7 +257,7 @@ where
                     continue;
                 }
 
-                for neighbour in self.neighbours.iter().map(|n| n + cell.clone()) {
+                for neighbour in self.neighbours.iter().map(|n| self.wrapped(n + cell.clone())) {
                     if unvisited.contains(&neighbour) {
                         to_visit.insert(neighbour);
                     }
@@ -223,7 +292,7 @@ where
                         opening
                             .requires
                             .iter()
-                            .all(|o| !self.walls.contains(&(cell.clone() + o)))
+                            .all(|o| !self.is_wall(&(cell.clone() + o)))
                     })
                     .count() > 2
         })
@@ -235,7 +304,7 @@ where
             let superset = room.iter().fold(HashSet::new(), |mut acc, cell| {
                 self.neighbours
                     .iter()
-                    .map(|n| n + cell)
+                    .map(|n| self.wrapped(n + cell))
                     .filter(|n| !self.walls.contains(n))
                     .for_each(|n| {
                         acc.insert(n);
@@ -260,7 +329,7 @@ where
                 let superset = room.iter().fold(HashSet::new(), |mut acc, cell| {
                     self.neighbours
                         .iter()
-                        .map(|n| n + cell)
+                        .map(|n| self.wrapped(n + cell))
                         .filter(|n| !self.walls.contains(n))
                         .for_each(|n| {
                             acc.insert(n);
@@ -281,24 +350,137 @@ where
         rooms
     }
 
-    fn is_on_border(&self, v: &::na::VectorN<isize, D>) -> bool {
-        let one = ::na::VectorN::<isize, D>::from_iterator((1..2).cycle());
-        !(v >= &one && v + one < self.size)
+    pub fn is_on_border(&self, v: &::na::VectorN<isize, D>) -> bool {
+        (0..D::dim())
+            .filter(|&i| !self.is_wrapped_axis(i))
+            .any(|i| v[i] < 1 || v[i] + 1 >= self.size[i])
+    }
+
+    fn is_wrapped_axis(&self, axis: usize) -> bool {
+        self.wrap && axis < 2
+    }
+
+    /// Bring coordinates of wrapped axes inside the maze
+    pub fn wrapped(&self, mut cell: ::na::VectorN<isize, D>) -> ::na::VectorN<isize, D> {
+        for i in 0..D::dim() {
+            if self.is_wrapped_axis(i) {
+                cell[i] = ((cell[i] % self.size[i]) + self.size[i]) % self.size[i];
+            }
+        }
+        cell
+    }
+
+    pub fn is_wall(&self, cell: &::na::VectorN<isize, D>) -> bool {
+        self.walls.contains(&self.wrapped(cell.clone()))
+    }
+
+    /// Wrap the maze and free border cells facing free cells on the opposite border
+    /// with `percent` probability
+    pub fn open_wrap<R: Rng>(&mut self, percent: f64, rng: &mut R) {
+        self.wrap = true;
+        for cell in self.iterate_maze() {
+            for i in 0..2 {
+                if cell[i] != 0 {
+                    continue;
+                }
+                let mut opposite = cell.clone();
+                opposite[i] = self.size[i] - 1;
+                let mut inner = cell.clone();
+                inner[i] = 1;
+                let mut opposite_inner = cell.clone();
+                opposite_inner[i] = self.size[i] - 2;
+
+                if !self.walls.contains(&inner) && !self.walls.contains(&opposite_inner)
+                    && rng.gen_range(0.0, 100.0) < percent
+                {
+                    self.walls.remove(&cell);
+                    self.walls.remove(&opposite);
+                }
+            }
+        }
+    }
+
+    /// World position of the copy of the cell the closest to the position
+    pub fn closest_to_world(
+        &self,
+        cell: &::na::VectorN<isize, D>,
+        pos: &::na::Vector3<f32>,
+    ) -> ::na::Vector3<f32> {
+        let mut world = self.to_world(cell);
+        for i in 0..D::dim() {
+            if self.is_wrapped_axis(i) {
+                let period = self.size[i] as f32 * self.scale[i];
+                world[i] -= ((world[i] - pos[i]) / period).round() * period;
+            }
+        }
+        world
+    }
+
+    /// Build a maze from layers of ascii rows: `#` is a wall, any other glyph is a free cell.
+    /// Missing glyphs are walls and border cells must be walls.
+    ///
+    /// Return the maze and the free cells marked with a glyph other than ` ` or `.`
+    fn from_ascii_layers(
+        layers: &[Vec<Vec<char>>],
+        scale: ::na::Vector3<f32>,
+    ) -> Result<(Self, Vec<(::na::VectorN<isize, D>, char)>), String> {
+        let width = layers
+            .iter()
+            .flat_map(|layer| layer.iter())
+            .map(|row| row.len())
+            .max()
+            .unwrap_or(0) as isize;
+        let height = layers.iter().map(|layer| layer.len()).max().unwrap_or(0) as isize;
+        if width == 0 || height == 0 {
+            return Err("empty maze".into());
+        }
+
+        let size = match D::dim() {
+            2 => Self::new_vec2(width, height),
+            3 => Self::new_vec3(width, height, layers.len() as isize),
+            _ => unimplemented!(),
+        };
+
+        let mut maze = Self::new_rectangle(size, scale);
+        let mut marks = vec![];
+        for cell in maze.iterate_maze() {
+            let layer = if D::dim() == 3 { cell[2] as usize } else { 0 };
+            let glyph = layers[layer]
+                .get(cell[1] as usize)
+                .and_then(|row| row.get(cell[0] as usize))
+                .cloned()
+                .unwrap_or('#');
+            match glyph {
+                '#' => {
+                    maze.walls.insert(cell);
+                }
+                _ if maze.is_on_border(&cell) => {
+                    return Err(format!(
+                        "free cell on border at {:?}",
+                        cell.iter().collect::<Vec<_>>()
+                    ))
+                }
+                ' ' | '.' => (),
+                glyph => marks.push((cell, glyph)),
+            }
+        }
+        Ok((maze, marks))
     }
 
     /// Filter allowed entry
     /// Return cell and its opening
     /// The vector returned may contains less than nbr cell if it can't dig further
-    pub fn dig_cells<F>(
+    pub fn dig_cells<F, R>(
         &mut self,
         nbr: usize,
         filter: F,
+        rng: &mut R,
     ) -> Vec<(::na::VectorN<isize, D>, ::na::VectorN<isize, D>)>
     where
         F: Fn(&::na::VectorN<isize, D>) -> bool,
+        R: Rng,
     {
         let mut res = vec![];
-        let mut rng = ::rand::thread_rng();
         let mut candidates = self.iterate_maze();
         candidates.retain(|cell| filter(cell));
 
@@ -306,19 +488,19 @@ where
             candidates.retain(|cell| {
                 self.neighbours
                     .iter()
-                    .map(|n| n + cell)
+                    .map(|n| self.wrapped(n + cell))
                     .filter(|n| !self.walls.contains(n))
                     .count() == 1 && !self.is_on_border(cell)
             });
             if candidates.is_empty() {
                 return res;
             }
-            let choosen = Range::new(0, candidates.len()).ind_sample(&mut rng);
+            let choosen = Range::new(0, candidates.len()).ind_sample(rng);
             let cell = candidates.swap_remove(choosen);
             self.walls.remove(&cell);
             let opening = self.neighbours
                 .iter()
-                .map(|n| n + cell.clone())
+                .map(|n| self.wrapped(n + cell.clone()))
                 .filter(|n| !self.walls.contains(n))
                 .next()
                 .unwrap();
@@ -366,23 +548,23 @@ where
         changes
     }
 
+    fn is_dead_end(&self, cell: &::na::VectorN<isize, D>) -> bool {
+        let neighbours_wall =
+            self.neighbours.iter().map(|n| self.wrapped(n + cell)).fold(0, |acc, n| {
+                if self.walls.contains(&n) {
+                    acc + 1
+                } else {
+                    acc
+                }
+            });
+        neighbours_wall >= self.neighbours.len() - 1
+    }
+
     pub fn fill_dead_corridors(&mut self) -> bool {
         let mut changes = false;
         loop {
             let mut corridors = self.compute_corridor_zones();
-            corridors.retain(|corridor| {
-                corridor.iter().any(|cell| {
-                    let neighbours_wall =
-                        self.neighbours.iter().map(|n| n + cell).fold(0, |acc, n| {
-                            if self.walls.contains(&n) {
-                                acc + 1
-                            } else {
-                                acc
-                            }
-                        });
-                    neighbours_wall >= self.neighbours.len() - 1
-                })
-            });
+            corridors.retain(|corridor| corridor.iter().any(|cell| self.is_dead_end(cell)));
             if corridors.len() == 0 {
                 break;
             }
@@ -394,6 +576,64 @@ where
         changes
     }
 
+    /// Fill dead corridors but keep each one with `keep` probability
+    ///
+    /// Return whereas change have been made
+    pub fn fill_some_dead_corridors<R: Rng>(&mut self, keep: f64, rng: &mut R) -> bool {
+        let mut kept = HashSet::new();
+        let mut changes = false;
+        loop {
+            // Sort corridors as set order isn't reproducible
+            let mut corridors = self.compute_corridor_zones()
+                .into_iter()
+                .filter(|corridor| {
+                    corridor.iter().all(|cell| !kept.contains(cell))
+                        && corridor.iter().any(|cell| self.is_dead_end(cell))
+                })
+                .map(|corridor| {
+                    let key = corridor.iter()
+                        .map(|cell| cell.iter().cloned().collect::<Vec<_>>())
+                        .min()
+                        .unwrap();
+                    (key, corridor)
+                })
+                .collect::<Vec<_>>();
+            if corridors.is_empty() {
+                break;
+            }
+            corridors.sort_by(|a, b| a.0.cmp(&b.0));
+
+            for (_, corridor) in corridors {
+                if rng.gen::<f64>() < keep {
+                    kept.extend(corridor);
+                } else {
+                    changes = true;
+                    self.walls.extend(corridor);
+                }
+            }
+        }
+        changes
+    }
+
+    /// Apply an operation of a generation recipe
+    pub fn apply<R: Rng>(&mut self, operation: &Operation, rng: &mut R) {
+        match *operation {
+            Operation::Reduce(size) => self.reduce(size),
+            Operation::Extend(size) => self.extend(size),
+            Operation::Circle => self.circle(),
+            Operation::OpenWrap(percent) => self.open_wrap(percent, rng),
+            Operation::FillSmallests => {
+                self.fill_smallests();
+            }
+            Operation::FillDeadRooms => while self.fill_dead_rooms() {},
+            Operation::FillDeadCorridors(keep) => if keep > 0.0 {
+                self.fill_some_dead_corridors(keep, rng);
+            } else {
+                while self.fill_dead_corridors() {}
+            },
+        }
+    }
+
     fn new_vec2(x: isize, y: isize) -> ::na::VectorN<isize, D> {
         let mut v = ::na::VectorN::<isize, D>::zeros();
         v[0] = x;
@@ -420,18 +660,72 @@ where
 
     #[inline]
     pub fn to_inner(&self, coords: &::na::Vector3<f32>) -> ::na::VectorN<isize, D> {
-        ::na::VectorN::<isize, D>::from_iterator(coords.iter().map(|&c| (c / self.scale) as isize))
+        let mut inner = ::na::VectorN::<isize, D>::from_iterator(
+            coords.iter().enumerate().map(|(i, &c)| (c / self.scale[i]) as isize),
+        );
+        if self.topology == Topology::Hexagonal {
+            // Round axial coordinates through cube coordinates
+            let r = (coords[1] / self.scale[1] - 0.5) / HEX_ROW_HEIGHT;
+            let q = coords[0] / self.scale[0] - 0.5 - r * 0.5;
+            let s = -q - r;
+            let (mut q_round, mut r_round, s_round) = (q.round(), r.round(), s.round());
+            let (q_diff, r_diff, s_diff) = ((q_round - q).abs(), (r_round - r).abs(), (s_round - s).abs());
+            if q_diff > r_diff && q_diff > s_diff {
+                q_round = -r_round - s_round;
+            } else if r_diff > s_diff {
+                r_round = -q_round - s_round;
+            }
+            inner[0] = q_round as isize;
+            inner[1] = r_round as isize;
+        }
+        self.wrapped(inner)
+    }
+
+    /// Cell of the position first then other cells touched by a body of the
+    /// radius on x and y axes
+    pub fn touched_cells(&self, pos: &::na::Vector3<f32>, radius: f32) -> Vec<::na::VectorN<isize, D>> {
+        let mut cells = vec![self.to_inner(pos)];
+        for &(dx, dy) in &[(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)] {
+            let cell = self.to_inner(&(pos + ::na::Vector3::new(dx, dy, 0.0) * radius));
+            if !cells.contains(&cell) {
+                cells.push(cell);
+            }
+        }
+        cells
     }
 
     #[inline]
     pub fn to_world(&self, coords: &::na::VectorN<isize, D>) -> ::na::Vector3<f32> {
-        let mut outer = ::na::Vector3::new(self.scale, self.scale, self.scale) * 0.5;
+        let mut outer = self.scale * 0.5;
         for i in 0..D::dim() {
-            outer[i] += (coords[i] as f32) * self.scale;
+            outer[i] += (coords[i] as f32) * self.scale[i];
+        }
+        if self.topology == Topology::Hexagonal {
+            outer[0] += (coords[1] as f32) * 0.5 * self.scale[0];
+            outer[1] = (0.5 + (coords[1] as f32) * HEX_ROW_HEIGHT) * self.scale[1];
         }
         outer
     }
 
+    /// World coordinates for cells of size 1
+    pub fn unscaled(&self, coords: &::na::Vector3<f32>) -> ::na::Vector3<f32> {
+        ::na::Vector3::new(
+            coords[0] / self.scale[0],
+            coords[1] / self.scale[1],
+            coords[2] / self.scale[2],
+        )
+    }
+
+    /// Unit direction from the center of a cell to the center of another one
+    pub fn direction(
+        &self,
+        from: &::na::VectorN<isize, D>,
+        to: &::na::VectorN<isize, D>,
+    ) -> ::na::Vector3<f32> {
+        let from = self.to_world(from);
+        (self.closest_to_world(to, &from) - from).normalize()
+    }
+
     pub fn inner_find_path(
         &self,
         pos: ::na::VectorN<isize, D>,
@@ -445,17 +739,25 @@ where
                     if opening
                         .requires
                         .iter()
-                        .all(|o| !self.walls.contains(&(o + cell.clone())))
+                        .all(|o| !self.is_wall(&(o + cell.clone())))
                     {
-                        res.push((opening.cell.clone() + cell, opening.cost));
+                        res.push((self.wrapped(opening.cell.clone() + cell), opening.cost));
                     }
                 }
                 res
             },
             |cell| {
-                let mut min = (cell[0] - goal[0]).abs();
+                let distance = |i: usize| {
+                    let d = (cell[i] - goal[i]).abs();
+                    if self.is_wrapped_axis(i) {
+                        d.min(self.size[i] - d)
+                    } else {
+                        d
+                    }
+                };
+                let mut min = distance(0);
                 for i in 1..D::dim() {
-                    min = min.min((cell[i] - goal[i]).abs());
+                    min = min.min(distance(i));
                 }
                 min * 10
             },
@@ -463,173 +765,399 @@ where
         ).map(|p| p.0)
     }
 
-    /// Generate partial reverse randomized_kruskal
-    /// `https://en.wikipedia.org/wiki/Maze_generation_algorithm#Randomized_Kruskal.27s_algorithm`
-    pub fn kruskal(
+    /// Dijkstra from goal over openings, openings are symmetric
+    pub fn distance_field(&self, goal: ::na::VectorN<isize, D>) -> DistanceField<D> {
+        let mut distances = HashMap::new();
+        let mut cells = vec![goal.clone()];
+        let mut heap = BinaryHeap::new();
+
+        distances.insert(goal.clone(), 0);
+        heap.push((Reverse(0), 0));
+
+        while let Some((Reverse(distance), index)) = heap.pop() {
+            let cell = cells[index].clone();
+            if distances[&cell] < distance {
+                continue;
+            }
+
+            for opening in self.openings.iter() {
+                if opening
+                    .requires
+                    .iter()
+                    .any(|o| self.is_wall(&(o + cell.clone())))
+                {
+                    continue;
+                }
+
+                let next = self.wrapped(opening.cell.clone() + cell.clone());
+                if (0..D::dim()).any(|i| next[i] < 0 || next[i] >= self.size[i]) {
+                    continue;
+                }
+
+                let next_distance = distance + opening.cost;
+                if distances.get(&next).map_or(true, |&d| next_distance < d) {
+                    distances.insert(next.clone(), next_distance);
+                    cells.push(next);
+                    heap.push((Reverse(next_distance), cells.len() - 1));
+                }
+            }
+        }
+
+        DistanceField { goal, distances }
+    }
+
+    /// Neighbour cell the closest to the goal of the field
+    pub fn next_cell(
+        &self,
+        field: &DistanceField<D>,
+        cell: &::na::VectorN<isize, D>,
+    ) -> Option<::na::VectorN<isize, D>> {
+        let mut best = field.distances.get(cell).cloned()?;
+        let mut next = None;
+        for opening in self.openings.iter() {
+            if opening
+                .requires
+                .iter()
+                .any(|o| self.is_wall(&(o + cell.clone())))
+            {
+                continue;
+            }
+
+            let neighbour = self.wrapped(opening.cell.clone() + cell.clone());
+            if let Some(&distance) = field.distances.get(&neighbour) {
+                if distance < best {
+                    best = distance;
+                    next = Some(neighbour);
+                }
+            }
+        }
+        next
+    }
+
+    /// Compute metrics of the maze for a path from start to end
+    ///
+    /// Return none if end can't be reached from start
+    pub fn analyse(
+        &self,
+        start: ::na::VectorN<isize, D>,
+        end: ::na::VectorN<isize, D>,
+    ) -> Option<Analysis> {
+        let path = self.inner_find_path(start.clone(), end.clone())?;
+
+        let free_neighbours = |cell: &::na::VectorN<isize, D>| {
+            self.neighbours
+                .iter()
+                .map(|n| self.wrapped(n + cell))
+                .filter(|n| !self.walls.contains(n))
+                .count()
+        };
+
+        let free_cells = self.iterate_maze()
+            .into_iter()
+            .filter(|cell| !self.walls.contains(cell))
+            .collect::<Vec<_>>();
+
+        let dead_ends = free_cells
+            .iter()
+            .filter(|cell| free_neighbours(cell) == 1)
+            .count();
+
+        let corridors = free_cells
+            .iter()
+            .filter(|cell| self.is_corridor(cell))
+            .count();
+
+        // Choices on each cell of the solution, not counting the way back
+        let choices = path.iter()
+            .take(path.len() - 1)
+            .enumerate()
+            .map(|(i, cell)| free_neighbours(cell).saturating_sub(if i == 0 { 0 } else { 1 }))
+            .sum::<usize>();
+
+        let distance = |a: &::na::VectorN<isize, D>, b: &::na::VectorN<isize, D>| {
+            self.unscaled(&(self.to_world(a) - self.to_world(b))).norm()
+        };
+        let walked = path.windows(2)
+            .map(|step| distance(&step[0], &step[1]))
+            .sum::<f32>();
+        let straight = distance(&start, &end);
+
+        Some(Analysis {
+            solution_length: path.len() - 1,
+            dead_ends,
+            branching_factor: if path.len() > 1 {
+                choices as f32 / (path.len() - 1) as f32
+            } else {
+                0.0
+            },
+            room_sizes: self.compute_room_zones()
+                .iter()
+                .map(|room| room.len())
+                .collect(),
+            corridor_ratio: if free_cells.is_empty() {
+                0.0
+            } else {
+                corridors as f32 / free_cells.len() as f32
+            },
+            tortuosity: if straight == 0.0 {
+                1.0
+            } else {
+                walked / straight
+            },
+        })
+    }
+
+    /// Generate a maze with the given algorithm
+    ///
+    /// Hexagonal mazes are always generated with `hexagonal`, `algorithm` and
+    /// `bug` are ignored for them
+    pub fn generate<R: Rng>(
+        algorithm: Algorithm,
+        topology: Topology,
         size: ::na::VectorN<isize, D>,
         percent: f64,
         bug: ::na::VectorN<isize, D>,
-        scale: f32,
+        scale: ::na::Vector3<f32>,
+        rng: &mut R,
     ) -> Self {
-        struct GridCell {
-            wall: bool,
-            group: usize,
+        if topology == Topology::Hexagonal {
+            return Self::hexagonal(size, percent, scale, rng);
         }
-
-        for size in size.iter() {
-            assert_eq!(size.wrapping_rem(2), 1);
+        match algorithm {
+            Algorithm::Kruskal => Self::kruskal(size, percent, bug, scale, rng),
+            Algorithm::RecursiveBacktracker => {
+                Self::recursive_backtracker(size, percent, bug, scale, rng)
+            }
+            Algorithm::Prim => Self::prim(size, percent, bug, scale, rng),
+            Algorithm::Wilson => Self::wilson(size, percent, bug, scale, rng),
         }
+    }
 
-        let mut grid = HashMap::new();
+    /// Dig a maze of hexagonal cells, walls are cells
+    ///
+    /// A wall cell is dug if it opens on exactly one free cell,
+    /// or with `percent` probability if it opens on several free cells not neighbouring each other
+    pub fn hexagonal<R: Rng>(
+        size: ::na::VectorN<isize, D>,
+        percent: f64,
+        scale: ::na::Vector3<f32>,
+        rng: &mut R,
+    ) -> Self {
+        let mut maze = Self::new_rectangle(size, scale);
+        maze.set_topology(Topology::Hexagonal);
+        for cell in maze.iterate_maze() {
+            maze.walls.insert(cell);
+        }
 
-        for (i, cell) in Self::iterate_area(&size).iter().enumerate() {
-            grid.insert(
-                cell.clone(),
-                GridCell {
-                    wall: false,
-                    group: i,
-                },
-            );
+        let inner = maze.iterate_maze()
+            .into_iter()
+            .filter(|cell| !maze.is_on_border(cell))
+            .collect::<Vec<_>>();
+        if inner.is_empty() {
+            return maze;
         }
 
-        let mut walls: Vec<Vec<::na::VectorN<isize, D>>> = Vec::new();
-        let mut x_wall = vec![]; // 1x3x3 wall centered on 0
-        let mut y_wall = vec![]; // 3x1x3 wall centered on 0
-        let mut z_wall = vec![]; // 3x3x1 wall centered on 0
+        let start = inner[Range::new(0, inner.len()).ind_sample(rng)].clone();
+        let mut frontier = vec![start.clone()];
+        let mut seen = HashSet::new();
+        seen.insert(start);
 
-        match D::dim() {
-            2 => {
-                for i in -1..2 {
-                    x_wall.push(Self::new_vec2(0, i));
-                    y_wall.push(Self::new_vec2(i, 0));
-                }
+        while !frontier.is_empty() {
+            let index = Range::new(0, frontier.len()).ind_sample(rng);
+            let cell = frontier.swap_remove(index);
 
-                for x in 1..size[0] / 2 + 1 {
-                    for y in 1..size[1] / 2 + 1 {
-                        if y != size[1] / 2 {
-                            walls.push(
-                                x_wall
-                                    .iter()
-                                    .map(|c| c + Self::new_vec2(x * 2 - 1 + bug[0], y * 2))
-                                    .collect(),
-                            );
-                        }
-                        if x != size[0] / 2 {
-                            walls.push(
-                                y_wall
-                                    .iter()
-                                    .map(|c| c + Self::new_vec2(x * 2, y * 2 - 1 + bug[1]))
-                                    .collect(),
-                            );
-                        }
-                    }
-                }
+            let free = maze.neighbours
+                .iter()
+                .map(|n| n + &cell)
+                .filter(|n| !maze.walls.contains(n))
+                .collect::<Vec<_>>();
+
+            let dig = free.len() <= 1 || (
+                rng.gen_range(0.0, 100.0) < percent
+                && free.iter().all(|a| {
+                    maze.neighbours.iter().all(|n| !free.contains(&(n + a)))
+                })
+            );
+            if !dig {
+                continue;
             }
-            3 => {
-                for i in -1..2 {
-                    for j in -1..2 {
-                        x_wall.push(Self::new_vec3(0, i, j));
-                        y_wall.push(Self::new_vec3(i, 0, j));
-                        z_wall.push(Self::new_vec3(i, j, 0));
-                    }
-                }
 
-                for x in 1..size[0] / 2 + 1 {
-                    for y in 1..size[1] / 2 + 1 {
-                        for z in 1..size[2] / 2 + 1 {
-                            let x_end = x == size[0] / 2;
-                            let y_end = y == size[1] / 2;
-                            let z_end = z == size[2] / 2;
-                            if !y_end && !z_end {
-                                walls.push(
-                                    x_wall
-                                        .iter()
-                                        .map(|c| {
-                                            c + Self::new_vec3(x * 2 - 1 + bug[0], y * 2, z * 2)
-                                        })
-                                        .collect(),
-                                );
-                            }
-                            if !x_end && !z_end {
-                                walls.push(
-                                    y_wall
-                                        .iter()
-                                        .map(|c| {
-                                            c + Self::new_vec3(x * 2, y * 2 - 1 + bug[1], z * 2)
-                                        })
-                                        .collect(),
-                                );
-                            }
-                            if !x_end && !y_end {
-                                walls.push(
-                                    z_wall
-                                        .iter()
-                                        .map(|c| {
-                                            c + Self::new_vec3(x * 2, y * 2, z * 2 - 1 + bug[2])
-                                        })
-                                        .collect(),
-                                );
-                            }
-                        }
-                    }
+            maze.walls.remove(&cell);
+            for neighbour in maze.neighbours.iter().map(|n| n + &cell) {
+                if !maze.is_on_border(&neighbour) && seen.insert(neighbour.clone()) {
+                    frontier.push(neighbour);
                 }
             }
-            _ => unimplemented!(),
         }
 
-        let mut rng = ::rand::thread_rng();
+        maze
+    }
 
+    /// Generate partial reverse randomized_kruskal
+    /// `https://en.wikipedia.org/wiki/Maze_generation_algorithm#Randomized_Kruskal.27s_algorithm`
+    pub fn kruskal<R: Rng>(
+        size: ::na::VectorN<isize, D>,
+        percent: f64,
+        bug: ::na::VectorN<isize, D>,
+        scale: ::na::Vector3<f32>,
+        rng: &mut R,
+    ) -> Self {
+        let mut forest = WallForest::new(&size, &bug);
+
+        let mut walls = (0..forest.walls.len()).collect::<Vec<_>>();
         let stop = ((walls.len() as f64) * (1. - percent / 100.)) as usize;
 
         while walls.len() > stop {
-            let i = ::rand::distributions::Range::new(0, walls.len()).ind_sample(&mut rng);
+            let i = Range::new(0, walls.len()).ind_sample(rng);
             let wall = walls.swap_remove(i);
+            forest.build(wall);
+        }
+
+        forest.into_maze(size, scale)
+    }
+
+    /// Generate partial reverse randomized depth-first search:
+    /// walls grow as one long branch until stuck, which gives long winding corridors
+    /// `https://en.wikipedia.org/wiki/Maze_generation_algorithm#Recursive_backtracker`
+    pub fn recursive_backtracker<R: Rng>(
+        size: ::na::VectorN<isize, D>,
+        percent: f64,
+        bug: ::na::VectorN<isize, D>,
+        scale: ::na::Vector3<f32>,
+        rng: &mut R,
+    ) -> Self {
+        let mut forest = WallForest::new(&size, &bug);
+        let goal = forest.goal(percent);
+        let mut stack = vec![];
+
+        while forest.considered < goal {
+            let current = match stack.last().cloned() {
+                Some(current) => current,
+                None => match forest.try_random_untried(rng) {
+                    Some(Some(wall)) => {
+                        stack.push(wall);
+                        continue;
+                    }
+                    Some(None) => continue,
+                    None => break,
+                },
+            };
 
-            let mut groups = HashSet::new();
-            for cell in &wall {
-                groups.insert(grid[cell].group);
+            let mut next = forest.untried_neighbours(current);
+            if next.is_empty() {
+                stack.pop();
+                continue;
+            }
+            let wall = next.swap_remove(Range::new(0, next.len()).ind_sample(rng));
+            forest.tried[wall] = true;
+            if forest.build(wall) {
+                stack.push(wall);
             }
-            let one_group = grid[&wall[0]].group; // a random group in the set
+        }
+
+        forest.into_maze(size, scale)
+    }
 
-            if groups.len() > 2 {
-                for cell in &wall {
-                    grid.get_mut(cell).unwrap().wall = true
+    /// Generate partial reverse randomized prim:
+    /// walls grow from random points of the structure, which gives lots of short branches
+    /// `https://en.wikipedia.org/wiki/Maze_generation_algorithm#Randomized_Prim's_algorithm`
+    pub fn prim<R: Rng>(
+        size: ::na::VectorN<isize, D>,
+        percent: f64,
+        bug: ::na::VectorN<isize, D>,
+        scale: ::na::Vector3<f32>,
+        rng: &mut R,
+    ) -> Self {
+        let mut forest = WallForest::new(&size, &bug);
+        let goal = forest.goal(percent);
+        let mut frontier = vec![];
+
+        while forest.considered < goal {
+            let wall = if frontier.is_empty() {
+                match forest.try_random_untried(rng) {
+                    Some(Some(wall)) => wall,
+                    Some(None) => continue,
+                    None => break,
                 }
-                for cell in grid.values_mut() {
-                    if groups.contains(&cell.group) {
-                        cell.group = one_group;
-                    }
+            } else {
+                let wall = frontier.swap_remove(Range::new(0, frontier.len()).ind_sample(rng));
+                if !forest.build(wall) {
+                    continue;
                 }
+                wall
+            };
+
+            for neighbour in forest.untried_neighbours(wall) {
+                forest.tried[neighbour] = true;
+                frontier.push(neighbour);
             }
         }
 
-        let mut walls = HashSet::new();
-        for (key, value) in grid {
-            if value.wall {
-                walls.insert(key);
+        forest.into_maze(size, scale)
+    }
+
+    /// Generate partial reverse wilson:
+    /// walls grow by loop-erased random walks, which gives mazes without bias
+    /// `https://en.wikipedia.org/wiki/Maze_generation_algorithm#Wilson's_algorithm`
+    pub fn wilson<R: Rng>(
+        size: ::na::VectorN<isize, D>,
+        percent: f64,
+        bug: ::na::VectorN<isize, D>,
+        scale: ::na::Vector3<f32>,
+        rng: &mut R,
+    ) -> Self {
+        let mut forest = WallForest::new(&size, &bug);
+        let goal = forest.goal(percent);
+
+        while forest.considered < goal {
+            let untried = (0..forest.walls.len())
+                .filter(|&wall| !forest.tried[wall])
+                .collect::<Vec<_>>();
+            if untried.is_empty() {
+                break;
             }
-        }
+            let start = untried[Range::new(0, untried.len()).ind_sample(rng)];
 
-        Maze {
-            size,
-            walls,
-            neighbours: Self::neighbours(),
-            openings: Self::openings(),
-            scale,
+            // Walls unable to reach the structure start a new one
+            if !forest.reaches_built(start) {
+                forest.tried[start] = true;
+                forest.build(start);
+                continue;
+            }
+
+            let mut path = vec![start];
+            while !forest.touches_built(*path.last().unwrap()) {
+                let neighbours = forest.neighbours(*path.last().unwrap());
+                let next = neighbours[Range::new(0, neighbours.len()).ind_sample(rng)];
+                if let Some(position) = path.iter().position(|&wall| wall == next) {
+                    path.truncate(position + 1);
+                } else {
+                    path.push(next);
+                }
+            }
+
+            for wall in path {
+                forest.tried[wall] = true;
+                forest.build(wall);
+                if forest.considered >= goal {
+                    break;
+                }
+            }
         }
+
+        forest.into_maze(size, scale)
     }
 
-    pub fn random_free(&self) -> ::na::VectorN<isize, D> {
+    pub fn random_free<R: Rng>(&self, rng: &mut R) -> ::na::VectorN<isize, D> {
         assert!(self.size.iter().all(|&s| s != 0));
 
         let ranges: Vec<_> = self.size.iter().map(|&s| Range::new(0, s)).collect();
-        let mut rng = ::rand::thread_rng();
 
         let mut vec =
-            ::na::VectorN::<isize, D>::from_iterator(ranges.iter().map(|r| r.ind_sample(&mut rng)));
+            ::na::VectorN::<isize, D>::from_iterator(ranges.iter().map(|r| r.ind_sample(rng)));
         while self.walls.contains(&vec) {
             vec = ::na::VectorN::<isize, D>::from_iterator(
-                ranges.iter().map(|r| r.ind_sample(&mut rng)),
+                ranges.iter().map(|r| r.ind_sample(rng)),
             );
         }
         vec
@@ -729,6 +1257,34 @@ where
         }
     }
 
+    /// Axial coordinates, third axis stacks layers of hexagons
+    fn hexagonal_neighbours() -> Vec<::na::VectorN<isize, D>> {
+        let mut neighbours = vec![
+            Self::new_vec2(-1, 0),
+            Self::new_vec2(1, 0),
+            Self::new_vec2(0, -1),
+            Self::new_vec2(0, 1),
+            Self::new_vec2(1, -1),
+            Self::new_vec2(-1, 1),
+        ];
+        if D::dim() == 3 {
+            neighbours.push(Self::new_vec3(0, 0, -1));
+            neighbours.push(Self::new_vec3(0, 0, 1));
+        }
+        neighbours
+    }
+
+    fn hexagonal_openings() -> Vec<Opening<D>> {
+        Self::hexagonal_neighbours()
+            .into_iter()
+            .map(|n| Opening {
+                cell: n.clone(),
+                cost: 10,
+                requires: vec![n],
+            })
+            .collect()
+    }
+
     fn openings() -> Vec<Opening<D>> {
         match D::dim() {
             2 => vec![
@@ -1038,6 +1594,404 @@ where
     }
 }
 
+/// Algorithm used to generate the walls of a maze
+///
+/// All algorithms build walls between cells, shifted by `bug`, without closing loops.
+/// `percent` is the percent of walls considered, a considered wall is built
+/// if it doesn't close a loop so 100 gives a perfect maze.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Algorithm {
+    Kruskal,
+    RecursiveBacktracker,
+    Prim,
+    Wilson,
+}
+
+impl Default for Algorithm {
+    fn default() -> Self {
+        Algorithm::Kruskal
+    }
+}
+
+/// Step of a generation recipe, see `Maze::apply`
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Operation {
+    /// Remove this number of cells on each border
+    Reduce(isize),
+    /// Add this number of free cells on each border
+    Extend(isize),
+    /// Wall around the maze
+    Circle,
+    /// Connect opposite borders with this percent of border openings
+    OpenWrap(f64),
+    /// Keep only the largest free zone
+    FillSmallests,
+    /// Fill rooms with a single way out
+    FillDeadRooms,
+    /// Fill corridors leading nowhere, each one is kept with this probability
+    FillDeadCorridors(f64),
+}
+
+/// Metrics of a maze, see `Maze::analyse`
+#[derive(Clone, Debug)]
+pub struct Analysis {
+    /// Number of steps of the path from start to end
+    pub solution_length: usize,
+    /// Number of free cells with only one free neighbour
+    pub dead_ends: usize,
+    /// Mean number of ways to go on each step of the solution
+    pub branching_factor: f32,
+    /// Size of each room zone
+    pub room_sizes: Vec<usize>,
+    /// Proportion of free cells that are corridors
+    pub corridor_ratio: f32,
+    /// Length of the solution divided by the distance from start to end
+    pub tortuosity: f32,
+}
+
+impl Analysis {
+    pub fn room_count(&self) -> usize {
+        self.room_sizes.len()
+    }
+
+    /// Estimation of the difficulty to find the solution, used to rank mazes
+    pub fn difficulty(&self) -> f32 {
+        self.solution_length as f32 * self.branching_factor.max(1.0) * self.tortuosity
+    }
+}
+
+impl ::std::fmt::Display for Analysis {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> Result<(), ::std::fmt::Error> {
+        writeln!(f, "solution length: {}", self.solution_length)?;
+        writeln!(f, "dead ends: {}", self.dead_ends)?;
+        writeln!(f, "branching factor: {:.2}", self.branching_factor)?;
+        writeln!(f, "rooms: {} {:?}", self.room_count(), self.room_sizes)?;
+        writeln!(f, "corridor ratio: {:.2}", self.corridor_ratio)?;
+        writeln!(f, "tortuosity: {:.2}", self.tortuosity)?;
+        write!(f, "difficulty: {:.1}", self.difficulty())
+    }
+}
+
+/// Height of a row of hexagons for a distance of 1 between neighbours
+pub const HEX_ROW_HEIGHT: f32 = 0.866_025_4;
+
+/// Adjacency of maze cells
+///
+/// Hexagonal cells use axial coordinates, in 3D they are stacked as hexagonal prisms
+///
+/// There is no triangular topology: the neighbours of a triangle depend on its
+/// orientation and `neighbours` is a single offset table for the whole maze
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Topology {
+    Square,
+    Hexagonal,
+}
+
+impl Default for Topology {
+    fn default() -> Self {
+        Topology::Square
+    }
+}
+
+struct GridCell {
+    wall: bool,
+    group: usize,
+}
+
+/// Walls built by reverse generation algorithms
+///
+/// Candidate walls are segments (planes in 3D) between the cells of the grid.
+/// A candidate is built only if it doesn't close a loop of walls.
+struct WallForest<D>
+where
+    D: ::na::Dim + ::na::DimName + Hash,
+    D::Value: Mul<typenum::UInt<typenum::UTerm, typenum::B1>, Output = D::Value>
+        + ::generic_array::ArrayLength<isize>,
+{
+    grid: HashMap<::na::VectorN<isize, D>, GridCell>,
+    walls: Vec<Vec<::na::VectorN<isize, D>>>,
+    cell_walls: HashMap<::na::VectorN<isize, D>, Vec<usize>>,
+    tried: Vec<bool>,
+    /// Walls passed to `build`, built or not
+    considered: usize,
+}
+
+impl<D> WallForest<D>
+where
+    D: ::na::Dim + ::na::DimName + Hash,
+    D::Value: Mul<typenum::UInt<typenum::UTerm, typenum::B1>, Output = D::Value>
+        + ::generic_array::ArrayLength<isize>,
+{
+    fn new(size: &::na::VectorN<isize, D>, bug: &::na::VectorN<isize, D>) -> Self {
+        for size in size.iter() {
+            assert_eq!(size.wrapping_rem(2), 1);
+        }
+
+        let mut grid = HashMap::new();
+
+        for (i, cell) in Maze::<D>::iterate_area(size).iter().enumerate() {
+            grid.insert(
+                cell.clone(),
+                GridCell {
+                    wall: false,
+                    group: i,
+                },
+            );
+        }
+
+        let mut walls: Vec<Vec<::na::VectorN<isize, D>>> = Vec::new();
+        let mut x_wall = vec![]; // 1x3x3 wall centered on 0
+        let mut y_wall = vec![]; // 3x1x3 wall centered on 0
+        let mut z_wall = vec![]; // 3x3x1 wall centered on 0
+
+        match D::dim() {
+            2 => {
+                for i in -1..2 {
+                    x_wall.push(Maze::<D>::new_vec2(0, i));
+                    y_wall.push(Maze::<D>::new_vec2(i, 0));
+                }
+
+                for x in 1..size[0] / 2 + 1 {
+                    for y in 1..size[1] / 2 + 1 {
+                        if y != size[1] / 2 {
+                            walls.push(
+                                x_wall
+                                    .iter()
+                                    .map(|c| c + Maze::<D>::new_vec2(x * 2 - 1 + bug[0], y * 2))
+                                    .collect(),
+                            );
+                        }
+                        if x != size[0] / 2 {
+                            walls.push(
+                                y_wall
+                                    .iter()
+                                    .map(|c| c + Maze::<D>::new_vec2(x * 2, y * 2 - 1 + bug[1]))
+                                    .collect(),
+                            );
+                        }
+                    }
+                }
+            }
+            3 => {
+                for i in -1..2 {
+                    for j in -1..2 {
+                        x_wall.push(Maze::<D>::new_vec3(0, i, j));
+                        y_wall.push(Maze::<D>::new_vec3(i, 0, j));
+                        z_wall.push(Maze::<D>::new_vec3(i, j, 0));
+                    }
+                }
+
+                for x in 1..size[0] / 2 + 1 {
+                    for y in 1..size[1] / 2 + 1 {
+                        for z in 1..size[2] / 2 + 1 {
+                            let x_end = x == size[0] / 2;
+                            let y_end = y == size[1] / 2;
+                            let z_end = z == size[2] / 2;
+                            if !y_end && !z_end {
+                                walls.push(
+                                    x_wall
+                                        .iter()
+                                        .map(|c| {
+                                            c + Maze::<D>::new_vec3(x * 2 - 1 + bug[0], y * 2, z * 2)
+                                        })
+                                        .collect(),
+                                );
+                            }
+                            if !x_end && !z_end {
+                                walls.push(
+                                    y_wall
+                                        .iter()
+                                        .map(|c| {
+                                            c + Maze::<D>::new_vec3(x * 2, y * 2 - 1 + bug[1], z * 2)
+                                        })
+                                        .collect(),
+                                );
+                            }
+                            if !x_end && !y_end {
+                                walls.push(
+                                    z_wall
+                                        .iter()
+                                        .map(|c| {
+                                            c + Maze::<D>::new_vec3(x * 2, y * 2, z * 2 - 1 + bug[2])
+                                        })
+                                        .collect(),
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+            _ => unimplemented!(),
+        }
+
+        let mut cell_walls = HashMap::new();
+        for (i, wall) in walls.iter().enumerate() {
+            for cell in wall {
+                cell_walls.entry(cell.clone()).or_insert_with(Vec::new).push(i);
+            }
+        }
+
+        WallForest {
+            grid,
+            tried: vec![false; walls.len()],
+            walls,
+            cell_walls,
+            considered: 0,
+        }
+    }
+
+    /// Number of walls to consider
+    fn goal(&self, percent: f64) -> usize {
+        ((self.walls.len() as f64) * percent / 100.) as usize
+    }
+
+    /// Build the wall if it doesn't close a loop
+    ///
+    /// Return whereas it has been built
+    fn build(&mut self, wall: usize) -> bool {
+        self.considered += 1;
+        let mut groups = HashSet::new();
+        for cell in &self.walls[wall] {
+            groups.insert(self.grid[cell].group);
+        }
+        let one_group = self.grid[&self.walls[wall][0]].group; // a random group in the set
+
+        if groups.len() > 2 {
+            for cell in &self.walls[wall] {
+                self.grid.get_mut(cell).unwrap().wall = true
+            }
+            for cell in self.grid.values_mut() {
+                if groups.contains(&cell.group) {
+                    cell.group = one_group;
+                }
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Try to build a random wall not tried yet
+    ///
+    /// Return None if all walls have been tried
+    fn try_random_untried<R: Rng>(&mut self, rng: &mut R) -> Option<Option<usize>> {
+        let untried = (0..self.walls.len())
+            .filter(|&wall| !self.tried[wall])
+            .collect::<Vec<_>>();
+        if untried.is_empty() {
+            return None;
+        }
+        let wall = untried[Range::new(0, untried.len()).ind_sample(rng)];
+        self.tried[wall] = true;
+        if self.build(wall) {
+            Some(Some(wall))
+        } else {
+            Some(None)
+        }
+    }
+
+    /// Walls sharing a cell with this wall
+    fn neighbours(&self, wall: usize) -> Vec<usize> {
+        let mut res = vec![];
+        for cell in &self.walls[wall] {
+            for &neighbour in &self.cell_walls[cell] {
+                if neighbour != wall && !res.contains(&neighbour) {
+                    res.push(neighbour);
+                }
+            }
+        }
+        res
+    }
+
+    fn untried_neighbours(&self, wall: usize) -> Vec<usize> {
+        let mut res = self.neighbours(wall);
+        res.retain(|&neighbour| !self.tried[neighbour]);
+        res
+    }
+
+    fn touches_built(&self, wall: usize) -> bool {
+        self.walls[wall].iter().any(|cell| self.grid[cell].wall)
+    }
+
+    fn reaches_built(&self, wall: usize) -> bool {
+        let mut visited = HashSet::new();
+        let mut to_visit = vec![wall];
+        while let Some(wall) = to_visit.pop() {
+            if self.touches_built(wall) {
+                return true;
+            }
+            if visited.insert(wall) {
+                to_visit.extend(self.neighbours(wall));
+            }
+        }
+        false
+    }
+
+    fn into_maze(self, size: ::na::VectorN<isize, D>, scale: ::na::Vector3<f32>) -> Maze<D> {
+        let mut walls = HashSet::new();
+        for (key, value) in self.grid {
+            if value.wall {
+                walls.insert(key);
+            }
+        }
+
+        Maze {
+            size,
+            walls,
+            neighbours: Maze::<D>::neighbours(),
+            openings: Maze::<D>::openings(),
+            topology: Topology::Square,
+            wrap: false,
+            scale,
+        }
+    }
+}
+
+/// Split text on empty lines, trailing whitespaces are ignored
+fn ascii_layers(text: &str) -> Vec<Vec<Vec<char>>> {
+    let mut layers = vec![];
+    let mut layer = vec![];
+    for line in text.lines() {
+        let line = line.trim_right();
+        if line.is_empty() {
+            if !layer.is_empty() {
+                layers.push(layer);
+                layer = vec![];
+            }
+        } else {
+            layer.push(line.chars().collect::<Vec<_>>());
+        }
+    }
+    if !layer.is_empty() {
+        layers.push(layer);
+    }
+    layers
+}
+
+impl Maze<::na::U2> {
+    /// Inverse of `Display`, see `from_ascii_layers` for glyphs
+    pub fn from_ascii(
+        text: &str,
+        scale: ::na::Vector3<f32>,
+    ) -> Result<(Self, Vec<(::na::Vector2<isize>, char)>), String> {
+        let layers = ascii_layers(text);
+        if layers.len() > 1 {
+            return Err(format!("expected one layer, found {}", layers.len()));
+        }
+        Self::from_ascii_layers(&layers, scale)
+    }
+}
+
+impl Maze<::na::U3> {
+    /// Layers of the 2D format separated by an empty line, starting at z = 0
+    pub fn from_ascii(
+        text: &str,
+        scale: ::na::Vector3<f32>,
+    ) -> Result<(Self, Vec<(::na::Vector3<isize>, char)>), String> {
+        Self::from_ascii_layers(&ascii_layers(text), scale)
+    }
+}
+
 impl ::std::fmt::Display for Maze<::na::U2> {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> Result<(), ::std::fmt::Error> {
         write!(f, "\n")?;
@@ -1054,3 +2008,26 @@ impl ::std::fmt::Display for Maze<::na::U2> {
         write!(f, "\n")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_ascii_round_trip() {
+        let mut rng = ::level::seeded_rng(3);
+        let mut maze = Maze::kruskal(
+            ::na::Vector2::new(11, 9),
+            100.0,
+            ::na::Vector2::new(0, 0),
+            ::na::Vector3::new(1.0, 1.0, 1.0),
+            &mut rng,
+        );
+        maze.circle();
+
+        let (parsed, marks) = Maze::<::na::U2>::from_ascii(&format!("{}", maze), maze.scale).unwrap();
+        assert!(marks.is_empty());
+        assert_eq!(parsed.size, maze.size);
+        assert_eq!(parsed.walls, maze.walls);
+    }
+}
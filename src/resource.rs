@@ -10,7 +10,7 @@ use std::sync::Arc;
 use std::fs::File;
 use std::path::PathBuf;
 use std::time::Duration;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::ffi::OsStr;
 use std::io::Read;
@@ -34,7 +34,14 @@ pub struct FpsCounter(pub usize);
 #[derive(Deserialize, Serialize)]
 pub struct Save {
     mouse_sensibility: f32,
+    /// Scores of the campaign
     scores: HashMap<usize, Score>,
+    /// Scores of level packs by pack name
+    #[serde(default)]
+    pack_scores: HashMap<String, HashMap<usize, Score>>,
+    /// Most parts cleared in a run of the endless mode
+    #[serde(default)]
+    endless_best_depth: usize,
     input_settings: InputSettings,
     fullscreen: bool,
     vulkan_device_uuid: Option<[u8; 16]>,
@@ -124,7 +131,7 @@ impl Score {
     }
 }
 
-const APP_INFO: AppInfo = AppInfo { name: "HyperZen Training", author: "thiolliere" };
+pub const APP_INFO: AppInfo = AppInfo { name: "HyperZen Training", author: "thiolliere" };
 const FILENAME: &str = "save.ron";
 
 lazy_static! {
@@ -163,6 +170,8 @@ impl Save {
             .unwrap_or(Save {
                 mouse_sensibility: ::CONFIG.mouse_sensibility,
                 scores: HashMap::new(),
+                pack_scores: HashMap::new(),
+                endless_best_depth: 0,
                 input_settings: InputSettings::default(),
                 fullscreen: true,
                 vulkan_device_uuid: None,
@@ -283,13 +292,37 @@ impl Save {
         self.mouse_sensibility
     }
 
-    pub fn insert_score(&mut self, level: usize, score: Duration) {
-        self.scores.entry(level).or_insert(Score::new()).insert(score);
+    pub fn insert_score(&mut self, pack: usize, level: usize, score: Duration) {
+        {
+            let scores = if pack == ::level::pack::CAMPAIGN {
+                &mut self.scores
+            } else {
+                self.pack_scores.entry(::level::pack::PACKS[pack].name.clone()).or_insert_with(HashMap::new)
+            };
+            scores.entry(level).or_insert(Score::new()).insert(score);
+        }
         self.save();
     }
 
-    pub fn score(&self, level: usize) -> Option<&Score> {
-        self.scores.get(&level)
+    /// Do nothing if depth isn't better
+    pub fn insert_endless_depth(&mut self, depth: usize) {
+        if depth > self.endless_best_depth {
+            self.endless_best_depth = depth;
+            self.save();
+        }
+    }
+
+    pub fn endless_best_depth(&self) -> usize {
+        self.endless_best_depth
+    }
+
+    pub fn score(&self, pack: usize, level: usize) -> Option<&Score> {
+        if pack == ::level::pack::CAMPAIGN {
+            self.scores.get(&level)
+        } else {
+            self.pack_scores.get(&::level::pack::PACKS[pack].name)
+                .and_then(|scores| scores.get(&level))
+        }
     }
 
     /// Do nothing if sensibility hasn't changed
@@ -381,11 +414,17 @@ pub struct LevelActions(pub Vec<LevelAction>);
 
 #[derive(Clone)]
 pub enum LevelAction {
-    Custom,
+    /// Custom level with the seed of a loaded code or a random one
+    Custom(Option<u64>),
+    /// First part of the endless mode
+    Endless,
     Next,
     Reset,
     ReturnHall,
-    Level(usize),
+    /// Hall of the pack
+    Hall(usize),
+    /// Level of the pack
+    Level(usize, usize),
 }
 
 pub enum Maze {
@@ -393,17 +432,145 @@ pub enum Maze {
     Maze3D(::maze::Maze<::na::U3>),
 }
 
-impl Maze {
-    pub fn find_path(
-        &self,
-        pos: ::na::Vector3<f32>,
-        goal: ::na::Vector3<f32>,
-    ) -> Option<Vec<::na::Vector3<f32>>> {
-        match *self {
-            Maze::Maze2D(ref maze) => maze.find_path(pos, goal),
-            Maze::Maze3D(ref maze) => maze.find_path(pos, goal),
+/// Distance of maze cells to the player
+///
+/// Rebuilt by DistanceFieldSystem only when the player changes cell
+pub enum DistanceField {
+    Empty,
+    Field2D(::maze::DistanceField<::na::U2>),
+    Field3D(::maze::DistanceField<::na::U3>),
+}
+
+impl DistanceField {
+    pub fn new() -> Self {
+        DistanceField::Empty
+    }
+
+    pub fn update(&mut self, maze: &Maze, player_pos: ::na::Vector3<f32>) {
+        match (&*self, maze) {
+            (&DistanceField::Field2D(ref field), &Maze::Maze2D(ref maze))
+                if field.goal == maze.to_inner(&player_pos) => return,
+            (&DistanceField::Field3D(ref field), &Maze::Maze3D(ref maze))
+                if field.goal == maze.to_inner(&player_pos) => return,
+            _ => (),
+        }
+
+        *self = match *maze {
+            Maze::Maze2D(ref maze) => {
+                DistanceField::Field2D(maze.distance_field(maze.to_inner(&player_pos)))
+            }
+            Maze::Maze3D(ref maze) => {
+                DistanceField::Field3D(maze.distance_field(maze.to_inner(&player_pos)))
+            }
+        };
+    }
+
+    /// Position of the center of the next cell toward the player
+    pub fn next_position(&self, maze: &Maze, pos: ::na::Vector3<f32>) -> Option<::na::Vector3<f32>> {
+        match (self, maze) {
+            (&DistanceField::Field2D(ref field), &Maze::Maze2D(ref maze)) => maze
+                .next_cell(field, &maze.to_inner(&pos))
+                .map(|cell| maze.closest_to_world(&cell, &pos)),
+            (&DistanceField::Field3D(ref field), &Maze::Maze3D(ref maze)) => maze
+                .next_cell(field, &maze.to_inner(&pos))
+                .map(|cell| maze.closest_to_world(&cell, &pos)),
+            _ => None,
+        }
+    }
+}
+
+/// Shifts of the walls of the current level, see ShiftingSystem
+pub struct ShiftingWalls {
+    /// Seconds between two shifts
+    pub period: Option<f32>,
+    pub timer: f32,
+    /// Number of walls toggled by a shift
+    pub shifts: usize,
+    /// Cell of the portal, always reachable by the player
+    pub end: ::na::Vector2<isize>,
+    pub rng: ::rand::StdRng,
+}
+
+impl ShiftingWalls {
+    /// Walls never shift
+    pub fn new() -> Self {
+        ShiftingWalls {
+            period: None,
+            timer: 0.0,
+            shifts: 0,
+            end: ::na::zero(),
+            rng: ::level::seeded_rng(0),
+        }
+    }
+}
+
+/// Encounters of the current level, see EncounterSystem
+pub struct Encounters(pub Vec<Encounter>);
+
+impl Encounters {
+    /// Encounters not cleared yet
+    pub fn remaining(&self) -> usize {
+        self.0.iter()
+            .filter(|encounter| match encounter.state {
+                EncounterState::Cleared => false,
+                _ => true,
+            })
+            .count()
+    }
+}
+
+pub struct Encounter {
+    /// Entities created when the player is in the room
+    pub entities: Vec<::entity::EntityConf>,
+    /// Cells of the room in the order entities are put in
+    pub cells: Vec<::na::Vector2<isize>>,
+    /// Cells around the room closed until the encounter is cleared
+    pub seal_cells: Vec<::na::Vector2<isize>>,
+    pub state: EncounterState,
+}
+
+pub enum EncounterState {
+    Waiting,
+    /// The player entered the room, entities must be created once the
+    /// player is inside the room and off its openings
+    Entered,
+    /// Created entities and walls sealing the room
+    Fighting {
+        entities: Vec<::specs::Entity>,
+        seals: Vec<::specs::Entity>,
+    },
+    Cleared,
+}
+
+/// Objective activating the teleport of the current level
+pub struct Objective(pub ::level::Objective);
+
+/// Waves of monsters of the current level, see WaveSystem
+pub struct Waves {
+    /// Delay of each wave with its entities and the cells they are put in
+    pub waves: Vec<(f32, Vec<::entity::EntityConf>, Vec<::na::Vector2<isize>>)>,
+    /// Index of the next wave
+    pub next: usize,
+    pub timer: f32,
+    /// The next wave must be created
+    pub coming: bool,
+}
+
+impl Waves {
+    /// No wave comes
+    pub fn new() -> Self {
+        Waves {
+            waves: vec![],
+            next: 0,
+            timer: 0.0,
+            coming: false,
         }
     }
+
+    /// Waves not created yet
+    pub fn remaining(&self) -> usize {
+        self.waves.len() - self.next
+    }
 }
 
 pub enum MenuStateState {
@@ -415,11 +582,18 @@ pub enum MenuStateState {
     CreateCustom,
 }
 
+/// Fields missing from older saves take their default value
 #[derive(PartialEq, Deserialize, Serialize, Clone)]
+#[serde(default)]
 pub struct CustomLevelConf {
+    pub three_d: bool,
     pub maze_size: i32,
+    /// Number of layers of 3D mazes
+    pub maze_size_z: i32,
     pub x_shift: bool,
     pub y_shift: bool,
+    pub z_shift: bool,
+    pub scale: [f32; 3],
     pub percent: f32,
     pub motion_less: i32,
     pub motion_less_eraser: i32,
@@ -430,14 +604,23 @@ pub struct CustomLevelConf {
     pub avoider: i32,
     pub avoider_eraser: i32,
     pub turret: i32,
+    pub avoider_generator: i32,
+    pub bouncer_generator: i32,
+    pub generator_salvo: i32,
+    pub generator_time_between_salvo_ms: i32,
+    pub generator_eraser_probability_percent: i32,
 }
 
-impl CustomLevelConf {
-    pub fn default() -> Self {
+impl Default for CustomLevelConf {
+    fn default() -> Self {
         CustomLevelConf {
+            three_d: false,
             maze_size: 10,
+            maze_size_z: 3,
             x_shift: false,
             y_shift: false,
+            z_shift: false,
+            scale: [1.0, 1.0, 1.0],
             percent: 5.0,
             motion_less: 3,
             motion_less_eraser: 3,
@@ -448,10 +631,220 @@ impl CustomLevelConf {
             avoider: 0,
             avoider_eraser: 0,
             turret: 0,
+            avoider_generator: 0,
+            bouncer_generator: 0,
+            generator_salvo: 3,
+            generator_time_between_salvo_ms: 3000,
+            generator_eraser_probability_percent: 0,
         }
     }
 }
 
+impl CustomLevelConf {
+    /// Level generated from this configuration
+    pub fn level(&self) -> ::level::Level {
+        let mut entities = BTreeMap::new();
+        entities.insert(::entity::EntityConf::MotionLess { eraser: false }, self.motion_less as usize);
+        entities.insert(::entity::EntityConf::MotionLess { eraser: true }, self.motion_less_eraser as usize);
+        entities.insert(::entity::EntityConf::Attracted { eraser: false }, self.attracted as usize);
+        entities.insert(::entity::EntityConf::Attracted { eraser: true }, self.attracted_eraser as usize);
+        entities.insert(::entity::EntityConf::Bouncer { eraser: false }, self.bouncer as usize);
+        entities.insert(::entity::EntityConf::Bouncer { eraser: true }, self.bouncer_eraser as usize);
+        entities.insert(::entity::EntityConf::Avoider { eraser: false }, self.avoider as usize);
+        entities.insert(::entity::EntityConf::Avoider { eraser: true }, self.avoider_eraser as usize);
+        entities.insert(::entity::EntityConf::Turret, self.turret as usize);
+        for &(generated_entity, nbr) in &[
+            (::component::GeneratedEntity::Avoider, self.avoider_generator),
+            (::component::GeneratedEntity::Bouncer, self.bouncer_generator),
+        ] {
+            entities.insert(::entity::EntityConf::Generator {
+                generated_entity,
+                salvo: self.generator_salvo as usize,
+                time_between_salvo_ms: self.generator_time_between_salvo_ms as usize,
+                eraser_probability_percent: self.generator_eraser_probability_percent as usize,
+            }, nbr as usize);
+        }
+
+        let size = self.maze_size as isize * 2 + 1;
+        let shift = |shift| if shift { 1 } else { 0 };
+        let scale = (self.scale[0], self.scale[1], self.scale[2]);
+
+        if self.three_d {
+            ::level::Level::KillAllKruskal3D(::level::kill_all_kruskal::Conf3D {
+                algorithm: ::maze::Algorithm::Kruskal,
+                topology: ::maze::Topology::Square,
+                mask: ::mask::Mask::Full,
+                recipe: None,
+                size: (size, size, self.maze_size_z as isize * 2 + 1),
+                scale,
+                percent: self.percent as f64,
+                bug: (shift(self.x_shift), shift(self.y_shift), shift(self.z_shift)),
+                entities,
+                densities: vec![],
+                objective: ::level::Objective::KillAll,
+            })
+        } else {
+            ::level::Level::KillAllKruskal2D(::level::kill_all_kruskal::Conf2D {
+                algorithm: ::maze::Algorithm::Kruskal,
+                topology: ::maze::Topology::Square,
+                mask: ::mask::Mask::Full,
+                wrap: None,
+                recipe: None,
+                floors: 1,
+                teleporters: 0,
+                teleport_monsters: false,
+                size: (size, size),
+                scale,
+                percent: self.percent as f64,
+                bug: (shift(self.x_shift), shift(self.y_shift)),
+                entities,
+                densities: vec![],
+                objective: ::level::Objective::KillAll,
+            })
+        }
+    }
+}
+
+/// Version of the format of custom level codes
+const CUSTOM_LEVEL_CODE_VERSION: u64 = 1;
+
+/// Capacity of the custom level code input, codes are shorter
+const CUSTOM_LEVEL_CODE_CAPACITY: usize = 256;
+
+// Ranges of custom level values in the menu
+const CUSTOM_FLAG: (i32, i32) = (0, 1);
+const CUSTOM_MAZE_SIZE: (i32, i32) = (5, 30);
+const CUSTOM_MAZE_SIZE_Z: (i32, i32) = (1, 10);
+const CUSTOM_SCALE: (f32, f32) = (0.5, 3.0);
+const CUSTOM_PERCENT: (f32, f32) = (0.0, 30.0);
+const CUSTOM_ENTITIES: (i32, i32) = (0, 100);
+const CUSTOM_GENERATORS: (i32, i32) = (0, 20);
+const CUSTOM_SALVO: (i32, i32) = (1, 10);
+const CUSTOM_TIME_BETWEEN_SALVO_MS: (i32, i32) = (500, 10000);
+const CUSTOM_PROBABILITY: (i32, i32) = (0, 100);
+
+impl CustomLevelConf {
+    /// Code of this configuration with the seed of its maze
+    ///
+    /// The code is its values in base 36 separated by `-`, floats are written
+    /// with their bits so the same maze is generated from the code
+    pub fn code(&self, seed: u64) -> String {
+        let flag = |flag| if flag { 1 } else { 0 };
+        let values = [
+            CUSTOM_LEVEL_CODE_VERSION,
+            flag(self.three_d),
+            self.maze_size as u64,
+            self.maze_size_z as u64,
+            flag(self.x_shift),
+            flag(self.y_shift),
+            flag(self.z_shift),
+            self.scale[0].to_bits() as u64,
+            self.scale[1].to_bits() as u64,
+            self.scale[2].to_bits() as u64,
+            self.percent.to_bits() as u64,
+            self.motion_less as u64,
+            self.motion_less_eraser as u64,
+            self.attracted as u64,
+            self.attracted_eraser as u64,
+            self.bouncer as u64,
+            self.bouncer_eraser as u64,
+            self.avoider as u64,
+            self.avoider_eraser as u64,
+            self.turret as u64,
+            self.avoider_generator as u64,
+            self.bouncer_generator as u64,
+            self.generator_salvo as u64,
+            self.generator_time_between_salvo_ms as u64,
+            self.generator_eraser_probability_percent as u64,
+            seed,
+        ];
+        values.iter()
+            .map(|&value| to_base_36(value))
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// Configuration and seed of a code made by `code`
+    pub fn from_code(code: &str) -> Result<(Self, u64), String> {
+        let values = code.trim().split('-')
+            .map(|value| u64::from_str_radix(value, 36)
+                .map_err(|e| format!("invalid value \"{}\": {}", value, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if values[0] != CUSTOM_LEVEL_CODE_VERSION {
+            return Err(format!("unknown code version {}", values[0]));
+        }
+        if values.len() != 26 {
+            return Err(format!("expect 26 values, found {}", values.len()));
+        }
+
+        // Values are checked against the ranges of the menu
+        let mut values = values.into_iter().skip(1);
+        let mut next = || values.next().unwrap();
+        let conf = CustomLevelConf {
+            three_d: code_int(next(), CUSTOM_FLAG)? != 0,
+            maze_size: code_int(next(), CUSTOM_MAZE_SIZE)?,
+            maze_size_z: code_int(next(), CUSTOM_MAZE_SIZE_Z)?,
+            x_shift: code_int(next(), CUSTOM_FLAG)? != 0,
+            y_shift: code_int(next(), CUSTOM_FLAG)? != 0,
+            z_shift: code_int(next(), CUSTOM_FLAG)? != 0,
+            scale: [
+                code_float(next(), CUSTOM_SCALE)?,
+                code_float(next(), CUSTOM_SCALE)?,
+                code_float(next(), CUSTOM_SCALE)?,
+            ],
+            percent: code_float(next(), CUSTOM_PERCENT)?,
+            motion_less: code_int(next(), CUSTOM_ENTITIES)?,
+            motion_less_eraser: code_int(next(), CUSTOM_ENTITIES)?,
+            attracted: code_int(next(), CUSTOM_ENTITIES)?,
+            attracted_eraser: code_int(next(), CUSTOM_ENTITIES)?,
+            bouncer: code_int(next(), CUSTOM_ENTITIES)?,
+            bouncer_eraser: code_int(next(), CUSTOM_ENTITIES)?,
+            avoider: code_int(next(), CUSTOM_ENTITIES)?,
+            avoider_eraser: code_int(next(), CUSTOM_ENTITIES)?,
+            turret: code_int(next(), CUSTOM_ENTITIES)?,
+            avoider_generator: code_int(next(), CUSTOM_GENERATORS)?,
+            bouncer_generator: code_int(next(), CUSTOM_GENERATORS)?,
+            generator_salvo: code_int(next(), CUSTOM_SALVO)?,
+            generator_time_between_salvo_ms: code_int(next(), CUSTOM_TIME_BETWEEN_SALVO_MS)?,
+            generator_eraser_probability_percent: code_int(next(), CUSTOM_PROBABILITY)?,
+        };
+        let seed = next();
+        Ok((conf, seed))
+    }
+}
+
+/// Value of a code in the range
+fn code_int(value: u64, (min, max): (i32, i32)) -> Result<i32, String> {
+    if value < min as u64 || value > max as u64 {
+        return Err(format!("value {} out of range {} to {}", value, min, max));
+    }
+    Ok(value as i32)
+}
+
+/// Float of the bits of a code value in the range
+fn code_float(value: u64, (min, max): (f32, f32)) -> Result<f32, String> {
+    if value > u32::max_value() as u64 {
+        return Err(format!("value {} isn't a float", value));
+    }
+    let value = f32::from_bits(value as u32);
+    if !value.is_finite() || value < min || value > max {
+        return Err(format!("value {} out of range {} to {}", value, min, max));
+    }
+    Ok(value)
+}
+
+fn to_base_36(mut value: u64) -> String {
+    let mut digits = vec![];
+    loop {
+        digits.push(::std::char::from_digit((value % 36) as u32, 36).unwrap());
+        value /= 36;
+        if value == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
 pub struct MenuState {
     pub state: MenuStateState,
     pub mouse_sensibility_input: f32,
@@ -476,12 +869,29 @@ pub struct MenuState {
     pub field_of_view_slider: f32,
 
     pub create_custom_button: bool,
+    pub endless_button: bool,
     pub custom_return_button: bool,
     pub custom_play_button: bool,
+    pub custom_load_code_button: bool,
+    pub custom_copy_code_button: bool,
+    pub custom_paste_code_button: bool,
     pub custom_level_conf: CustomLevelConf,
+    /// Code of the last custom level played or the one to load
+    pub custom_level_code: ImString,
+    pub invalid_custom_level_code: bool,
+    /// Error of the last copy or paste of the code
+    pub clipboard_error: Option<String>,
+    /// Seed of the current level, none in halls
+    pub level_seed: Option<u64>,
 }
 
 impl MenuState {
+    pub fn set_custom_level_code(&mut self, code: &str) {
+        self.custom_level_code.clear();
+        self.custom_level_code.push_str(code);
+        self.invalid_custom_level_code = false;
+    }
+
     pub fn paused(&self) -> bool {
         match self.state {
             MenuStateState::Input(_) => true,
@@ -518,9 +928,17 @@ impl MenuState {
             effect_volume_slider: save.music_volume(),
 
             create_custom_button: false,
+            endless_button: false,
             custom_return_button: false,
             custom_play_button: false,
+            custom_load_code_button: false,
+            custom_copy_code_button: false,
+            custom_paste_code_button: false,
             custom_level_conf: save.custom_level_conf(),
+            custom_level_code: ImString::with_capacity(CUSTOM_LEVEL_CODE_CAPACITY),
+            invalid_custom_level_code: false,
+            clipboard_error: None,
+            level_seed: None,
         }
     }
 
@@ -550,8 +968,12 @@ impl MenuState {
                         self.continue_button = ui.button(&ImString::new(text.continue_.clone()), button_size);
                         self.return_hall_button = ui.button(&ImString::new(text.return_to_hall.clone()), button_size);
                         self.create_custom_button = ui.button(&ImString::new(text.create_custom_level.clone()), button_size);
+                        self.endless_button = ui.button(&ImString::new(format!("{} ({} {})", text.endless, text.best_depth, save.endless_best_depth())), button_size);
                         self.help_button = ui.button(&ImString::new(text.help.clone()), button_size);
                         self.quit_button = ui.button(&ImString::new(text.quit.clone()), button_size);
+                        if let Some(seed) = self.level_seed {
+                            ui.text(&ImString::new(format!("{} {}", text.level_seed, seed)));
+                        }
                         ui.separator();
                         ui.text(&ImString::new(text.audio.clone()));
                         ui.slider_float(&ImString::new(text.music_volume.clone()), &mut self.effect_volume_slider, 0.0, 1.0).build();
@@ -623,23 +1045,53 @@ impl MenuState {
                         self.custom_play_button = ui.button(&ImString::new(text.play.clone()), button_size);
                         self.custom_return_button = ui.button(&ImString::new(text.return_.clone()), button_size);
                         ui.separator();
+                        ui.text(&ImString::new(text.code.clone()));
+                        ui.input_text(&ImString::new("##code"), &mut self.custom_level_code).build();
+                        self.custom_load_code_button = ui.button(&ImString::new(text.load_code.clone()), medium_button_size);
+                        ui.same_line(0.0);
+                        self.custom_copy_code_button = ui.button(&ImString::new(text.copy_code.clone()), medium_button_size);
+                        ui.same_line(0.0);
+                        self.custom_paste_code_button = ui.button(&ImString::new(text.paste_code.clone()), medium_button_size);
+                        if self.invalid_custom_level_code {
+                            ui.text(&ImString::new(text.invalid_code.clone()));
+                        }
+                        if let Some(ref error) = self.clipboard_error {
+                            ui.text(&ImString::new(error.clone()));
+                        }
+                        ui.separator();
                         ui.text(&ImString::new(text.configuration.clone()));
 
-                        ui.slider_int(&ImString::new(text.size.clone()), &mut self.custom_level_conf.maze_size, 5, 30).build();
+                        ui.checkbox(&ImString::new(text.three_d.clone()), &mut self.custom_level_conf.three_d);
+                        ui.slider_int(&ImString::new(text.size.clone()), &mut self.custom_level_conf.maze_size, CUSTOM_MAZE_SIZE.0, CUSTOM_MAZE_SIZE.1).build();
+                        if self.custom_level_conf.three_d {
+                            ui.slider_int(&ImString::new(text.layers.clone()), &mut self.custom_level_conf.maze_size_z, CUSTOM_MAZE_SIZE_Z.0, CUSTOM_MAZE_SIZE_Z.1).build();
+                        }
                         ui.checkbox(&ImString::new(text.x_shift.clone()), &mut self.custom_level_conf.x_shift);
                         ui.same_line(0.0);
                         ui.checkbox(&ImString::new(text.y_shift.clone()), &mut self.custom_level_conf.y_shift);
-
-                        ui.slider_float(&ImString::new(text.filling.clone()), &mut self.custom_level_conf.percent, 0.0, 30.0).build();
-                        ui.slider_int(&ImString::new(text.motionless.clone()), &mut self.custom_level_conf.motion_less, 0, 100).build();
-                        ui.slider_int(&ImString::new(text.motionless_eraser.clone()), &mut self.custom_level_conf.motion_less_eraser, 0, 100).build();
-                        ui.slider_int(&ImString::new(text.attracted.clone()), &mut self.custom_level_conf.attracted, 0, 100).build();
-                        ui.slider_int(&ImString::new(text.attracted_eraser.clone()), &mut self.custom_level_conf.attracted_eraser, 0, 100).build();
-                        ui.slider_int(&ImString::new(text.bouncer.clone()), &mut self.custom_level_conf.bouncer, 0, 100).build();
-                        ui.slider_int(&ImString::new(text.bouncer_eraser.clone()), &mut self.custom_level_conf.bouncer_eraser, 0, 100).build();
-                        ui.slider_int(&ImString::new(text.avoider.clone()), &mut self.custom_level_conf.avoider, 0, 100).build();
-                        ui.slider_int(&ImString::new(text.avoider_eraser.clone()), &mut self.custom_level_conf.avoider_eraser, 0, 100).build();
-                        ui.slider_int(&ImString::new(text.turret.clone()), &mut self.custom_level_conf.turret, 0, 100).build();
+                        if self.custom_level_conf.three_d {
+                            ui.same_line(0.0);
+                            ui.checkbox(&ImString::new(text.z_shift.clone()), &mut self.custom_level_conf.z_shift);
+                        }
+                        ui.slider_float(&ImString::new(text.scale_x.clone()), &mut self.custom_level_conf.scale[0], CUSTOM_SCALE.0, CUSTOM_SCALE.1).build();
+                        ui.slider_float(&ImString::new(text.scale_y.clone()), &mut self.custom_level_conf.scale[1], CUSTOM_SCALE.0, CUSTOM_SCALE.1).build();
+                        ui.slider_float(&ImString::new(text.scale_z.clone()), &mut self.custom_level_conf.scale[2], CUSTOM_SCALE.0, CUSTOM_SCALE.1).build();
+
+                        ui.slider_float(&ImString::new(text.filling.clone()), &mut self.custom_level_conf.percent, CUSTOM_PERCENT.0, CUSTOM_PERCENT.1).build();
+                        ui.slider_int(&ImString::new(text.motionless.clone()), &mut self.custom_level_conf.motion_less, CUSTOM_ENTITIES.0, CUSTOM_ENTITIES.1).build();
+                        ui.slider_int(&ImString::new(text.motionless_eraser.clone()), &mut self.custom_level_conf.motion_less_eraser, CUSTOM_ENTITIES.0, CUSTOM_ENTITIES.1).build();
+                        ui.slider_int(&ImString::new(text.attracted.clone()), &mut self.custom_level_conf.attracted, CUSTOM_ENTITIES.0, CUSTOM_ENTITIES.1).build();
+                        ui.slider_int(&ImString::new(text.attracted_eraser.clone()), &mut self.custom_level_conf.attracted_eraser, CUSTOM_ENTITIES.0, CUSTOM_ENTITIES.1).build();
+                        ui.slider_int(&ImString::new(text.bouncer.clone()), &mut self.custom_level_conf.bouncer, CUSTOM_ENTITIES.0, CUSTOM_ENTITIES.1).build();
+                        ui.slider_int(&ImString::new(text.bouncer_eraser.clone()), &mut self.custom_level_conf.bouncer_eraser, CUSTOM_ENTITIES.0, CUSTOM_ENTITIES.1).build();
+                        ui.slider_int(&ImString::new(text.avoider.clone()), &mut self.custom_level_conf.avoider, CUSTOM_ENTITIES.0, CUSTOM_ENTITIES.1).build();
+                        ui.slider_int(&ImString::new(text.avoider_eraser.clone()), &mut self.custom_level_conf.avoider_eraser, CUSTOM_ENTITIES.0, CUSTOM_ENTITIES.1).build();
+                        ui.slider_int(&ImString::new(text.turret.clone()), &mut self.custom_level_conf.turret, CUSTOM_ENTITIES.0, CUSTOM_ENTITIES.1).build();
+                        ui.slider_int(&ImString::new(text.avoider_generator.clone()), &mut self.custom_level_conf.avoider_generator, CUSTOM_GENERATORS.0, CUSTOM_GENERATORS.1).build();
+                        ui.slider_int(&ImString::new(text.bouncer_generator.clone()), &mut self.custom_level_conf.bouncer_generator, CUSTOM_GENERATORS.0, CUSTOM_GENERATORS.1).build();
+                        ui.slider_int(&ImString::new(text.generator_salvo.clone()), &mut self.custom_level_conf.generator_salvo, CUSTOM_SALVO.0, CUSTOM_SALVO.1).build();
+                        ui.slider_int(&ImString::new(text.generator_time_between_salvo.clone()), &mut self.custom_level_conf.generator_time_between_salvo_ms, CUSTOM_TIME_BETWEEN_SALVO_MS.0, CUSTOM_TIME_BETWEEN_SALVO_MS.1).build();
+                        ui.slider_int(&ImString::new(text.generator_eraser_probability.clone()), &mut self.custom_level_conf.generator_eraser_probability_percent, CUSTOM_PROBABILITY.0, CUSTOM_PROBABILITY.1).build();
                     });
             }
             _ => (),
@@ -696,6 +1148,8 @@ pub struct Text {
     pub continue_: String,
     pub return_to_hall: String,
     pub create_custom_level: String,
+    pub endless: String,
+    pub best_depth: String,
     pub help: String,
     pub quit: String,
     pub audio: String,
@@ -717,15 +1171,32 @@ pub struct Text {
     pub play: String,
     pub return_: String,
     pub configuration: String,
+    pub code: String,
+    pub load_code: String,
+    pub copy_code: String,
+    pub paste_code: String,
+    pub invalid_code: String,
+    pub level_seed: String,
     pub size: String,
     pub x_shift: String,
     pub y_shift: String,
+    pub z_shift: String,
+    pub three_d: String,
+    pub layers: String,
+    pub scale_x: String,
+    pub scale_y: String,
+    pub scale_z: String,
     pub filling: String,
     pub attracted_eraser: String,
     pub avoider_eraser: String,
     pub bouncer_eraser: String,
     pub motionless_eraser: String,
     pub turret: String,
+    pub avoider_generator: String,
+    pub bouncer_generator: String,
+    pub generator_salvo: String,
+    pub generator_time_between_salvo: String,
+    pub generator_eraser_probability: String,
     pub input: String,
     pub set_input_or_escape: String,
     pub restart: String,
@@ -739,6 +1210,11 @@ pub struct Text {
     pub motionless: String,
     pub go_to_portal: String,
     pub remains: String,
+    pub waves: String,
+    pub orbs: String,
+    pub checkpoints: String,
+    pub erase: String,
+    pub encounters: String,
     pub mouse_middle: String,
     pub mouse_left: String,
     pub mouse_right: String,
@@ -805,3 +1281,50 @@ fn test_text() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_level_code_round_trip() {
+        let mut conf = CustomLevelConf::default();
+        conf.three_d = true;
+        conf.maze_size_z = 4;
+        conf.z_shift = true;
+        conf.scale = [0.75, 1.5, 2.25];
+        conf.percent = 12.3;
+        conf.turret = 7;
+        conf.bouncer_generator = 2;
+
+        for &seed in &[0, 42, u64::max_value()] {
+            let (decoded, decoded_seed) = CustomLevelConf::from_code(&conf.code(seed)).unwrap();
+            assert!(decoded == conf);
+            assert_eq!(decoded_seed, seed);
+        }
+    }
+
+    #[test]
+    fn custom_level_code_rejects_malformed() {
+        let code = CustomLevelConf::default().code(42);
+        let replace = |index: usize, value: &str| {
+            let mut values = code.split('-').collect::<Vec<_>>();
+            values[index] = value;
+            values.join("-")
+        };
+
+        assert!(CustomLevelConf::from_code("").is_err());
+        assert!(CustomLevelConf::from_code("not a code").is_err());
+        assert!(CustomLevelConf::from_code(&replace(0, "2")).is_err());
+        assert!(CustomLevelConf::from_code(&code[..code.rfind('-').unwrap()]).is_err());
+        // maze_size of u64::MAX
+        assert!(CustomLevelConf::from_code(&replace(2, "3w5e11264sgsf")).is_err());
+        // maze_size out of the menu range
+        assert!(CustomLevelConf::from_code(&replace(2, &to_base_36(31))).is_err());
+        // NaN and negative scales
+        assert!(CustomLevelConf::from_code(&replace(7, &to_base_36(::std::f32::NAN.to_bits() as u64))).is_err());
+        assert!(CustomLevelConf::from_code(&replace(7, &to_base_36((-1.0f32).to_bits() as u64))).is_err());
+        // motion_less of -1 as written by older codes
+        assert!(CustomLevelConf::from_code(&replace(11, &to_base_36(-1i32 as u64))).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use specs::Join;
 
+/// Activate the teleport once the objective of the level is done
 pub struct ActivateSystem;
 
 impl<'a> ::specs::System<'a> for ActivateSystem {
@@ -9,18 +10,36 @@ impl<'a> ::specs::System<'a> for ActivateSystem {
         ::specs::ReadStorage<'a, ::component::Avoider>,
         ::specs::ReadStorage<'a, ::component::Bouncer>,
         ::specs::ReadStorage<'a, ::component::Motionless>,
+        ::specs::ReadStorage<'a, ::component::Orb>,
+        ::specs::ReadStorage<'a, ::component::Checkpoint>,
         ::specs::WriteStorage<'a, ::component::StaticDraw>,
         ::specs::Fetch<'a, ::resource::Audio>,
+        ::specs::Fetch<'a, ::resource::Waves>,
+        ::specs::Fetch<'a, ::resource::Encounters>,
+        ::specs::Fetch<'a, ::resource::Objective>,
+        ::specs::Fetch<'a, ::resource::ErasedStatus>,
         ::specs::FetchMut<'a, ::resource::Activated>,
     );
 
-    fn run(&mut self, (activateds, attracted, avoider, bouncer, motionless, mut static_draws, audio, mut activated): Self::SystemData) {
-        if !activated.0
-            && attracted.join().next().is_none()
-            && avoider.join().next().is_none()
-            && bouncer.join().next().is_none()
-            && motionless.join().next().is_none()
-        {
+    fn run(&mut self, (activateds, attracted, avoider, bouncer, motionless, orbs, checkpoints, mut static_draws, audio, waves, encounters, objective, erased_status, mut activated): Self::SystemData) {
+        if activated.0 {
+            return;
+        }
+
+        let done = match objective.0 {
+            ::level::Objective::KillAll => waves.remaining() == 0
+                && !waves.coming
+                && encounters.remaining() == 0
+                && attracted.join().next().is_none()
+                && avoider.join().next().is_none()
+                && bouncer.join().next().is_none()
+                && motionless.join().next().is_none(),
+            ::level::Objective::Collect(_) => orbs.join().next().is_none(),
+            ::level::Objective::Checkpoints(_) => checkpoints.join().next().is_none(),
+            ::level::Objective::Erase(percent) => erased_status.amount * 100.0 >= percent,
+        };
+
+        if done {
             audio.play_unspatial(::audio::Sound::AllKilled);
             activated.0 = true;
             for (_, draw) in (&activateds, &mut static_draws).join() {
@@ -11,11 +11,12 @@ impl<'a> ::specs::System<'a> for AvoiderControlSystem {
         ::specs::WriteStorage<'a, ::component::Momentum>,
         ::specs::Fetch<'a, ::resource::PhysicWorld>,
         ::specs::Fetch<'a, ::resource::Maze>,
+        ::specs::Fetch<'a, ::resource::DistanceField>,
     );
 
     fn run(
         &mut self,
-        (players, aims, bodies, mut avoiders, mut momentums, physic_world, maze): Self::SystemData,
+        (players, aims, bodies, mut avoiders, mut momentums, physic_world, maze, distance_field): Self::SystemData,
     ) {
         let (_, player_aim, player_body) = (&players, &aims, &bodies).join().next().unwrap();
         let player_aim_dir = player_aim.rotation * ::na::Vector3::x();
@@ -37,12 +38,7 @@ impl<'a> ::specs::System<'a> for AvoiderControlSystem {
             };
 
             if recompute_goal {
-                if let Some(path) = maze.find_path(
-                    avoider_pos.translation.vector,
-                    player_pos.translation.vector,
-                ) {
-                    avoider.goal = path.get(1).cloned();
-                }
+                avoider.goal = distance_field.next_position(&maze, avoider_pos.translation.vector);
             }
 
             let goal_coef = 1f32;
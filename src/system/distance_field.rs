@@ -0,0 +1,23 @@
+use specs::Join;
+
+pub struct DistanceFieldSystem;
+
+impl<'a> ::specs::System<'a> for DistanceFieldSystem {
+    type SystemData = (
+        ::specs::ReadStorage<'a, ::component::Player>,
+        ::specs::ReadStorage<'a, ::component::PhysicBody>,
+        ::specs::Fetch<'a, ::resource::PhysicWorld>,
+        ::specs::Fetch<'a, ::resource::Maze>,
+        ::specs::FetchMut<'a, ::resource::DistanceField>,
+    );
+
+    fn run(
+        &mut self,
+        (players, bodies, physic_world, maze, mut distance_field): Self::SystemData,
+    ) {
+        let (_, player_body) = (&players, &bodies).join().next().unwrap();
+        let player_pos = player_body.get(&physic_world).position().translation.vector;
+
+        distance_field.update(&maze, player_pos);
+    }
+}
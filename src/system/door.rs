@@ -0,0 +1,65 @@
+use specs::Join;
+
+/// Delete entered switches and open doors whose key is deleted
+pub struct DoorSystem;
+
+impl<'a> ::specs::System<'a> for DoorSystem {
+    type SystemData = (
+        ::specs::ReadStorage<'a, ::component::Door>,
+        ::specs::ReadStorage<'a, ::component::DoorSwitch>,
+        ::specs::ReadStorage<'a, ::component::Proximitor>,
+        ::specs::WriteStorage<'a, ::component::PhysicSensor>,
+        ::specs::WriteStorage<'a, ::component::PhysicBody>,
+        ::specs::FetchMut<'a, ::resource::Maze>,
+        ::specs::FetchMut<'a, ::resource::DistanceField>,
+        ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+        ::specs::Entities<'a>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            doors,
+            door_switches,
+            proximitors,
+            mut sensors,
+            mut bodies,
+            mut maze,
+            mut distance_field,
+            mut physic_world,
+            entities,
+        ): Self::SystemData,
+    ) {
+        for (_, proximitor, sensor, entity) in (&door_switches, &proximitors, &mut sensors, &*entities).join() {
+            if !proximitor.intersections.is_empty() {
+                sensor.remove(&mut physic_world);
+                entities.delete(entity).unwrap();
+            }
+        }
+
+        let mut opened = false;
+        for (door, body, entity) in (&doors, &mut bodies, &*entities).join() {
+            if entities.is_alive(door.key) {
+                continue;
+            }
+            let pos = body.get(&physic_world).position().translation.vector;
+            match *maze {
+                ::resource::Maze::Maze2D(ref mut maze) => {
+                    let cell = maze.to_inner(&pos);
+                    maze.walls.remove(&cell);
+                }
+                ::resource::Maze::Maze3D(ref mut maze) => {
+                    let cell = maze.to_inner(&pos);
+                    maze.walls.remove(&cell);
+                }
+            }
+            body.remove(&mut physic_world);
+            entities.delete(entity).unwrap();
+            opened = true;
+        }
+
+        if opened {
+            *distance_field = ::resource::DistanceField::new();
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use specs::Join;
+use resource::EncounterState;
+
+/// Start encounters whose room is entered and open the room once they are cleared
+///
+/// Entities of the encounter are created by GameSystem
+pub struct EncounterSystem;
+
+impl<'a> ::specs::System<'a> for EncounterSystem {
+    type SystemData = (
+        ::specs::ReadStorage<'a, ::component::RoomSensor>,
+        ::specs::ReadStorage<'a, ::component::Proximitor>,
+        ::specs::ReadStorage<'a, ::component::Life>,
+        ::specs::WriteStorage<'a, ::component::PhysicSensor>,
+        ::specs::WriteStorage<'a, ::component::PhysicBody>,
+        ::specs::FetchMut<'a, ::resource::Encounters>,
+        ::specs::FetchMut<'a, ::resource::Maze>,
+        ::specs::FetchMut<'a, ::resource::DistanceField>,
+        ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+        ::specs::Entities<'a>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            room_sensors,
+            proximitors,
+            lives,
+            mut sensors,
+            mut bodies,
+            mut encounters,
+            mut maze,
+            mut distance_field,
+            mut physic_world,
+            entities,
+        ): Self::SystemData,
+    ) {
+        for (room_sensor, proximitor, sensor, entity) in (&room_sensors, &proximitors, &mut sensors, &*entities).join() {
+            let encounter = &mut encounters.0[room_sensor.encounter];
+            if let EncounterState::Waiting = encounter.state {
+                if proximitor.intersections.is_empty() {
+                    continue;
+                }
+                encounter.state = EncounterState::Entered;
+            }
+            sensor.remove(&mut physic_world);
+            entities.delete(entity).unwrap();
+        }
+
+        let mut opened = false;
+        for encounter in &mut encounters.0 {
+            // Only living entities can be killed
+            let cleared = match encounter.state {
+                EncounterState::Fighting { entities: ref created, .. } => created.iter()
+                    .all(|&e| !entities.is_alive(e) || lives.get(e).is_none()),
+                _ => false,
+            };
+            if !cleared {
+                continue;
+            }
+
+            if let EncounterState::Fighting { ref seals, .. } = encounter.state {
+                for &seal in seals {
+                    bodies.get_mut(seal).unwrap().remove(&mut physic_world);
+                    entities.delete(seal).unwrap();
+                }
+            }
+            if let ::resource::Maze::Maze2D(ref mut maze) = *maze {
+                for cell in &encounter.seal_cells {
+                    maze.walls.remove(cell);
+                }
+            }
+            opened |= !encounter.seal_cells.is_empty();
+            encounter.state = EncounterState::Cleared;
+        }
+
+        if opened {
+            *distance_field = ::resource::DistanceField::new();
+        }
+    }
+}
@@ -1,25 +1,34 @@
 use nphysics::resolution::{AccumulatedImpulseSolver, CorrectionMode};
 use std::time::Duration;
-use std::collections::HashMap;
 
 pub struct GameSystem {
     current_level: Option<Level>,
+    forced_seed: Option<u64>,
 }
 
 #[derive(Clone, Copy)]
 enum Level {
-    Hall,
-    Custom,
-    Level(usize, usize),
+    /// Hall of the pack
+    Hall(usize),
+    /// Custom level with its seed kept on reset
+    Custom(u64),
+    /// Part of a level of the pack
+    Level(usize, usize, usize),
+    /// Part of the endless mode at its depth
+    Endless(usize),
 }
 
 impl GameSystem {
     pub fn new() -> Self {
         GameSystem {
             current_level: None,
+            forced_seed: ::std::env::var("HYPERZEN_TRAINING_SEED").ok().and_then(|val| val.parse::<u64>().ok()),
         }
     }
     pub fn run(&mut self, world: &mut ::specs::World) {
+        ::level::waves::create_coming_wave(world);
+        ::level::encounters::create_entered_encounters(world);
+
         let action = {
             let mut level_actions = world.write_resource::<::resource::LevelActions>();
             let action = level_actions.0.first().cloned();
@@ -28,47 +37,66 @@ impl GameSystem {
         };
 
         let recreate_level = match (self.current_level, action) {
-            (None, _) => Some(Level::Hall),
-            (Some(Level::Hall), Some(::resource::LevelAction::Level(level))) => {
+            (None, _) => Some(Level::Hall(::level::pack::CAMPAIGN)),
+            (Some(Level::Hall(_)), Some(::resource::LevelAction::Level(pack, level))) => {
                 world.write_resource::<::resource::Audio>().play_unspatial(::audio::Sound::Portal);
-                if ::CONFIG.levels[level].len() != 0 {
-                    Some(Level::Level(level, 0))
+                if ::level::pack::PACKS[pack].levels[level].len() != 0 {
+                    Some(Level::Level(pack, level, 0))
                 } else {
                     let mut game_duration = world.write_resource::<::resource::GameDuration>();
-                    world.write_resource::<::resource::Save>().insert_score(level, Duration::new(0, 0));
+                    world.write_resource::<::resource::Save>().insert_score(pack, level, Duration::new(0, 0));
                     game_duration.0 = Duration::new(0, 0);
-                    Some(Level::Hall)
+                    Some(Level::Hall(pack))
                 }
             },
-            (Some(Level::Level(level, part)), Some(::resource::LevelAction::Next)) => {
+            (Some(Level::Hall(_)), Some(::resource::LevelAction::Hall(pack))) => {
+                world.write_resource::<::resource::Audio>().play_unspatial(::audio::Sound::Portal);
+                Some(Level::Hall(pack))
+            },
+            (Some(Level::Level(pack, level, part)), Some(::resource::LevelAction::Next)) => {
                 world.write_resource::<::resource::Audio>().play_unspatial(::audio::Sound::Portal);
-                if ::CONFIG.levels[level].len() > part + 1 {
-                    Some(Level::Level(level, part+1))
+                if ::level::pack::PACKS[pack].levels[level].len() > part + 1 {
+                    Some(Level::Level(pack, level, part+1))
                 } else {
                     let mut game_duration = world.write_resource::<::resource::GameDuration>();
-                    world.write_resource::<::resource::Save>().insert_score(level, game_duration.0);
+                    world.write_resource::<::resource::Save>().insert_score(pack, level, game_duration.0);
                     game_duration.0 = Duration::new(0, 0);
-                    Some(Level::Hall)
+                    Some(Level::Hall(pack))
                 }
             },
+            (Some(Level::Endless(depth)), Some(::resource::LevelAction::Next)) => {
+                world.write_resource::<::resource::Audio>().play_unspatial(::audio::Sound::Portal);
+                world.write_resource::<::resource::Save>().insert_endless_depth(depth + 1);
+                Some(Level::Endless(depth + 1))
+            },
+            // The run ends on the first death
+            (Some(Level::Endless(_)), Some(::resource::LevelAction::Reset)) => Some(Level::Hall(::level::pack::CAMPAIGN)),
             (current_level, Some(::resource::LevelAction::Reset)) => current_level,
-            (_, Some(::resource::LevelAction::Custom)) => Some(Level::Custom),
-            (_, Some(::resource::LevelAction::ReturnHall)) => Some(Level::Hall),
+            (_, Some(::resource::LevelAction::Endless)) => Some(Level::Endless(0)),
+            (_, Some(::resource::LevelAction::Custom(seed))) => {
+                Some(Level::Custom(seed.or(self.forced_seed).unwrap_or_else(|| ::rand::random())))
+            },
+            (Some(Level::Level(pack, ..)), Some(::resource::LevelAction::ReturnHall)) => Some(Level::Hall(pack)),
+            (_, Some(::resource::LevelAction::ReturnHall)) => Some(Level::Hall(::level::pack::CAMPAIGN)),
             (Some(_), None) => None,
 
-            (Some(Level::Hall), Some(::resource::LevelAction::Next)) => {
+            (Some(Level::Hall(pack)), Some(::resource::LevelAction::Next)) => {
                 println!("INTERNAL ERROR: called next in hall");
-                Some(Level::Hall)
+                Some(Level::Hall(pack))
             },
-            (Some(Level::Custom), Some(::resource::LevelAction::Next)) => {
+            (Some(Level::Custom(_)), Some(::resource::LevelAction::Next)) => {
                 println!("INTERNAL ERROR: called next in custom");
-                Some(Level::Hall)
+                Some(Level::Hall(::level::pack::CAMPAIGN))
             },
             (Some(Level::Level(..)), Some(::resource::LevelAction::Level(..)))
-            | (Some(Level::Custom), Some(::resource::LevelAction::Level(..)))
+            | (Some(Level::Custom(_)), Some(::resource::LevelAction::Level(..)))
+            | (Some(Level::Level(..)), Some(::resource::LevelAction::Hall(..)))
+            | (Some(Level::Custom(_)), Some(::resource::LevelAction::Hall(..)))
+            | (Some(Level::Endless(_)), Some(::resource::LevelAction::Level(..)))
+            | (Some(Level::Endless(_)), Some(::resource::LevelAction::Hall(..)))
             => {
-                println!("INTERNAL ERROR: called go to level outside hall");
-                Some(Level::Hall)
+                println!("INTERNAL ERROR: called go to level or hall outside hall");
+                Some(Level::Hall(::level::pack::CAMPAIGN))
             },
         };
 
@@ -101,35 +129,46 @@ impl GameSystem {
             world.write_resource::<::resource::ErasedStatus>().clear();
 
             world.add_resource(::resource::DepthCoef(1.0));
+            world.add_resource(::resource::DistanceField::new());
+            world.add_resource(::resource::ShiftingWalls::new());
+            world.add_resource(::resource::Waves::new());
+            world.add_resource(::resource::Encounters(vec![]));
+            world.add_resource(::resource::Objective(::level::Objective::KillAll));
             world.add_resource(physic_world);
 
-            match level {
-                Level::Hall => ::level::create_hall(world),
-                Level::Level(level, part) => ::CONFIG.levels[level][part].create(world),
-                Level::Custom => {
-                    let conf = world.read_resource::<::resource::Save>().custom_level_conf();
-
-                    let mut entities = HashMap::new();
-                    entities.insert(::entity::EntityConf::MotionLess { eraser: false }, conf.motion_less as usize);
-                    entities.insert(::entity::EntityConf::MotionLess { eraser: true }, conf.motion_less_eraser as usize);
-                    entities.insert(::entity::EntityConf::Attracted { eraser: false }, conf.attracted as usize);
-                    entities.insert(::entity::EntityConf::Attracted { eraser: true }, conf.attracted_eraser as usize);
-                    entities.insert(::entity::EntityConf::Bouncer { eraser: false }, conf.bouncer as usize);
-                    entities.insert(::entity::EntityConf::Bouncer { eraser: true }, conf.bouncer_eraser as usize);
-                    entities.insert(::entity::EntityConf::Avoider { eraser: false }, conf.avoider as usize);
-                    entities.insert(::entity::EntityConf::Avoider { eraser: true }, conf.avoider_eraser as usize);
-                    entities.insert(::entity::EntityConf::Turret, conf.turret as usize);
+            let seed = match level {
+                Level::Custom(seed) => seed,
+                _ => self.forced_seed.unwrap_or_else(|| ::rand::random()),
+            };
+            if world.read_resource::<::resource::DebugMode>().0 {
+                println!("level seed: {}", seed);
+            }
+            world.write_resource::<::resource::MenuState>().level_seed = match level {
+                Level::Hall(_) => None,
+                _ => Some(seed),
+            };
 
-                    ::level::Level::KillAllKruskal2D(::level::kill_all_kruskal::Conf2D {
-                        size: (conf.maze_size as isize * 2+1, conf.maze_size as isize * 2+1),
-                        percent: conf.percent as f64,
-                        bug: (
-                            if conf.x_shift { 1 } else { 0 },
-                            if conf.y_shift { 1 } else { 0 },
-                        ),
-                        entities,
-                    }).create(world);
+            let created = match level {
+                Level::Hall(pack) => {
+                    ::level::create_hall(pack, world);
+                    Ok(())
                 },
+                Level::Level(pack, level, part) => ::level::pack::PACKS[pack].levels[level][part].create(seed, world),
+                Level::Endless(depth) => ::CONFIG.endless.level(depth)
+                    .and_then(|level| level.create(seed, world)),
+                Level::Custom(_) => {
+                    let conf = world.read_resource::<::resource::Save>().custom_level_conf();
+                    world.write_resource::<::resource::MenuState>().set_custom_level_code(&conf.code(seed));
+                    conf.level().create(seed, world)
+                },
+            };
+
+            // Nothing is created in the world when generation fails
+            if let Err(e) = created {
+                ::show_message::show(format!("Failed to generate level: {}", e));
+                world.write_resource::<::resource::MenuState>().level_seed = None;
+                self.current_level = Some(Level::Hall(::level::pack::CAMPAIGN));
+                ::level::create_hall(::level::pack::CAMPAIGN, world);
             }
 
             world.maintain();
@@ -74,7 +74,7 @@ impl<'a> ::specs::System<'a> for GeneratorSystem {
                             &mut physic_world,
                             &entities,
                         ),
-                    }
+                    };
                 }
             }
         }
@@ -8,17 +8,33 @@ impl<'a> ::specs::System<'a> for HelpSystem {
         ::specs::ReadStorage<'a, ::component::Avoider>,
         ::specs::ReadStorage<'a, ::component::Bouncer>,
         ::specs::ReadStorage<'a, ::component::Motionless>,
+        ::specs::ReadStorage<'a, ::component::Orb>,
+        ::specs::ReadStorage<'a, ::component::Checkpoint>,
+        ::specs::Fetch<'a, ::resource::Waves>,
+        ::specs::Fetch<'a, ::resource::Encounters>,
+        ::specs::Fetch<'a, ::resource::Objective>,
+        ::specs::Fetch<'a, ::resource::ErasedStatus>,
         ::specs::FetchMut<'a, ::resource::Help>,
         ::specs::Fetch<'a, ::resource::Text>,
     );
 
-    fn run(&mut self, (attracted, avoider, bouncer, motionless, mut help, text): Self::SystemData) {
-        let r = [
-            (attracted.join().count(), &text.attracted),
-            (avoider.join().count(), &text.avoider),
-            (bouncer.join().count(), &text.bouncer),
-            (motionless.join().count(), &text.motionless),
-        ];
+    fn run(&mut self, (attracted, avoider, bouncer, motionless, orbs, checkpoints, waves, encounters, objective, erased_status, mut help, text): Self::SystemData) {
+        let r = match objective.0 {
+            ::level::Objective::KillAll => vec![
+                (attracted.join().count(), &text.attracted),
+                (avoider.join().count(), &text.avoider),
+                (bouncer.join().count(), &text.bouncer),
+                (motionless.join().count(), &text.motionless),
+                (waves.remaining(), &text.waves),
+                (encounters.remaining(), &text.encounters),
+            ],
+            ::level::Objective::Collect(_) => vec![(orbs.join().count(), &text.orbs)],
+            ::level::Objective::Checkpoints(_) => vec![(checkpoints.join().count(), &text.checkpoints)],
+            ::level::Objective::Erase(percent) => {
+                let left = (percent - erased_status.amount * 100.0).max(0.0).ceil() as usize;
+                vec![(left, &text.erase)]
+            }
+        };
 
         let remaining = r
             .iter()
@@ -79,15 +79,46 @@ impl<'a> ::specs::System<'a> for MenuPauseControlSystem {
                 }
             }
             ::resource::MenuStateState::CreateCustom => {
+                if menu_state.custom_copy_code_button {
+                    menu_state.clipboard_error = ::copy_paste::set_text(menu_state.custom_level_code.to_str()).err();
+                }
+                if menu_state.custom_paste_code_button {
+                    let pasted = ::copy_paste::get_text();
+                    match pasted {
+                        Ok(code) => {
+                            menu_state.set_custom_level_code(code.trim());
+                            menu_state.clipboard_error = None;
+                        },
+                        Err(e) => menu_state.clipboard_error = Some(e),
+                    }
+                }
+
+                let mut loaded_seed = None;
+                if menu_state.custom_load_code_button {
+                    let loaded = ::resource::CustomLevelConf::from_code(menu_state.custom_level_code.to_str());
+                    match loaded {
+                        Ok((conf, seed)) => {
+                            menu_state.custom_level_conf = conf;
+                            loaded_seed = Some(seed);
+                        },
+                        Err(_) => menu_state.invalid_custom_level_code = true,
+                    }
+                }
+
                 save.set_custom_level_conf_lazy(menu_state.custom_level_conf.clone());
 
+                if let Some(seed) = loaded_seed {
+                    menu_state.state = ::resource::MenuStateState::Game;
+                    level_actions.0.push(::resource::LevelAction::Custom(Some(seed)));
+                }
+
                 if menu_state.custom_return_button {
                     menu_state.state = ::resource::MenuStateState::Pause;
                 }
 
                 if menu_state.custom_play_button {
                     menu_state.state = ::resource::MenuStateState::Game;
-                    level_actions.0.push(::resource::LevelAction::Custom);
+                    level_actions.0.push(::resource::LevelAction::Custom(None));
                 }
             }
             ::resource::MenuStateState::Input(input) => {
@@ -154,6 +185,11 @@ impl<'a> ::specs::System<'a> for MenuPauseControlSystem {
                     menu_state.state = ::resource::MenuStateState::CreateCustom;
                 }
 
+                if menu_state.endless_button {
+                    level_actions.0.push(::resource::LevelAction::Endless);
+                    menu_state.state = ::resource::MenuStateState::Game;
+                }
+
                 if menu_state.continue_button {
                     menu_state.state = ::resource::MenuStateState::Game;
                 }
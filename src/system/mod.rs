@@ -5,6 +5,7 @@ mod depth_coef;
 mod menu_control;
 mod player_control;
 mod avoider_control;
+mod distance_field;
 mod bouncer_control;
 mod follower;
 mod turret_control;
@@ -22,11 +23,18 @@ mod reducer;
 mod activated;
 mod help;
 mod player_death;
+mod wrap;
+mod shifting;
+mod door;
+mod wave;
+mod objective;
+mod encounter;
 
 pub use self::teleport::TeleportSystem;
 pub use self::menu_control::{MenuGameControlSystem, MenuPauseControlSystem};
 pub use self::player_control::PlayerControlSystem;
 pub use self::avoider_control::AvoiderControlSystem;
+pub use self::distance_field::DistanceFieldSystem;
 pub use self::bouncer_control::BouncerControlSystem;
 pub use self::follower::FollowPlayerSystem;
 pub use self::turret_control::TurretControlSystem;
@@ -46,4 +54,10 @@ pub use self::depth_ball::DepthBallSystem;
 pub use self::attracted::AttractedSystem;
 pub use self::activated::ActivateSystem;
 pub use self::player_death::PlayerDeathSystem;
+pub use self::wrap::WrapSystem;
+pub use self::shifting::ShiftingSystem;
+pub use self::door::DoorSystem;
+pub use self::wave::WaveSystem;
+pub use self::objective::ObjectiveSystem;
+pub use self::encounter::EncounterSystem;
 pub use self::help::HelpSystem;
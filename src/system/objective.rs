@@ -0,0 +1,42 @@
+use specs::Join;
+
+/// Collect touched orbs and reach the next checkpoint
+pub struct ObjectiveSystem;
+
+impl<'a> ::specs::System<'a> for ObjectiveSystem {
+    type SystemData = (
+        ::specs::ReadStorage<'a, ::component::Orb>,
+        ::specs::ReadStorage<'a, ::component::Checkpoint>,
+        ::specs::ReadStorage<'a, ::component::Proximitor>,
+        ::specs::WriteStorage<'a, ::component::PhysicSensor>,
+        ::specs::WriteStorage<'a, ::component::StaticDraw>,
+        ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+        ::specs::Entities<'a>,
+    );
+
+    fn run(
+        &mut self,
+        (orbs, checkpoints, proximitors, mut sensors, mut static_draws, mut physic_world, entities): Self::SystemData,
+    ) {
+        for (_, proximitor, sensor, entity) in (&orbs, &proximitors, &mut sensors, &*entities).join() {
+            if !proximitor.intersections.is_empty() {
+                sensor.remove(&mut physic_world);
+                entities.delete(entity).unwrap();
+            }
+        }
+
+        // Only the checkpoint of lowest index can be reached
+        let next = (&checkpoints, &*entities).join()
+            .min_by_key(|&(checkpoint, _)| checkpoint.index)
+            .map(|(_, entity)| entity);
+        if let Some(next) = next {
+            if let Some(draw) = static_draws.get_mut(next) {
+                draw.color = ::CONFIG.next_checkpoint_color;
+            }
+            if !proximitors.get(next).unwrap().intersections.is_empty() {
+                sensors.get_mut(next).unwrap().remove(&mut physic_world);
+                entities.delete(next).unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use rand::distributions::{IndependentSample, Range};
+use specs::Join;
+use std::collections::HashSet;
+
+/// Open and close shifting walls on timer and triggers
+///
+/// A wall doesn't close on a body nor if it disconnects the player from the portal
+pub struct ShiftingSystem;
+
+impl<'a> ::specs::System<'a> for ShiftingSystem {
+    type SystemData = (
+        ::specs::ReadStorage<'a, ::component::Player>,
+        ::specs::ReadStorage<'a, ::component::Momentum>,
+        ::specs::WriteStorage<'a, ::component::ShiftTrigger>,
+        ::specs::ReadStorage<'a, ::component::Proximitor>,
+        ::specs::WriteStorage<'a, ::component::ShiftingWall>,
+        ::specs::WriteStorage<'a, ::component::PhysicBody>,
+        ::specs::WriteStorage<'a, ::component::StaticDraw>,
+        ::specs::Fetch<'a, ::resource::UpdateTime>,
+        ::specs::FetchMut<'a, ::resource::ShiftingWalls>,
+        ::specs::FetchMut<'a, ::resource::Maze>,
+        ::specs::FetchMut<'a, ::resource::DistanceField>,
+        ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+        ::specs::Entities<'a>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            players,
+            momentums,
+            mut shift_triggers,
+            proximitors,
+            mut shifting_walls,
+            mut bodies,
+            mut static_draws,
+            update_time,
+            mut shifting,
+            mut maze,
+            mut distance_field,
+            mut physic_world,
+            entities,
+        ): Self::SystemData,
+    ) {
+        if shifting.shifts == 0 {
+            return;
+        }
+        let maze = match *maze {
+            ::resource::Maze::Maze2D(ref mut maze) => maze,
+            _ => return,
+        };
+
+        // Shift only when the player enters a trigger, not while it stays in
+        let mut shift = false;
+        for (trigger, proximitor) in (&mut shift_triggers, &proximitors).join() {
+            let touched = !proximitor.intersections.is_empty();
+            shift |= touched && !trigger.touched;
+            trigger.touched = touched;
+        }
+        if let Some(period) = shifting.period {
+            shifting.timer += update_time.0;
+            if shifting.timer >= period {
+                shifting.timer -= period;
+                shift = true;
+            }
+        }
+        if !shift {
+            return;
+        }
+
+        let player_cell = match (&players, &bodies).join().next() {
+            Some((_, body)) => maze.to_inner(&body.get(&physic_world).position().translation.vector),
+            None => return,
+        };
+
+        // Cells touched by moving bodies
+        let mut occupied = HashSet::new();
+        for (_, body) in (&momentums, &bodies).join() {
+            let pos = body.get(&physic_world).position().translation.vector;
+            occupied.extend(maze.touched_cells(&pos, ::CONFIG.player_radius));
+        }
+
+        let mut candidates = (&*entities, &shifting_walls).join()
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+
+        let mut changes = false;
+        for _ in 0..shifting.shifts {
+            if candidates.is_empty() {
+                break;
+            }
+            let index = Range::new(0, candidates.len()).ind_sample(&mut shifting.rng);
+            let entity = candidates.swap_remove(index);
+            let wall = shifting_walls.get_mut(entity).unwrap();
+
+            if wall.closed {
+                maze.walls.remove(&wall.cell);
+                bodies.get_mut(entity).unwrap().remove(&mut physic_world);
+                bodies.remove(entity);
+                for &(draw, _) in &wall.draws {
+                    static_draws.remove(draw);
+                }
+            } else {
+                if occupied.contains(&wall.cell) {
+                    continue;
+                }
+
+                maze.walls.insert(wall.cell);
+                let connected = maze.compute_zones(|maze, cell| !maze.walls.contains(cell))
+                    .iter()
+                    .any(|zone| zone.contains(&player_cell) && zone.contains(&shifting.end));
+                if !connected {
+                    maze.walls.remove(&wall.cell);
+                    continue;
+                }
+
+                let body = ::entity::wall_cube_body(maze.to_world(&wall.cell), maze.scale / 2.0);
+                ::component::PhysicBody::add(entity, body, &mut bodies, &mut physic_world);
+                for &(draw, ref static_draw) in &wall.draws {
+                    static_draws.insert(draw, static_draw.clone());
+                }
+            }
+            wall.closed = !wall.closed;
+            changes = true;
+        }
+
+        if changes {
+            *distance_field = ::resource::DistanceField::new();
+        }
+    }
+}
@@ -6,15 +6,29 @@ impl<'a> ::specs::System<'a> for TeleportSystem {
     type SystemData = (
         ::specs::ReadStorage<'a, ::component::Teleport>,
         ::specs::ReadStorage<'a, ::component::Proximitor>,
+        ::specs::WriteStorage<'a, ::component::PhysicBody>,
         ::specs::Fetch<'a, ::resource::Activated>,
         ::specs::FetchMut<'a, ::resource::LevelActions>,
+        ::specs::FetchMut<'a, ::resource::PhysicWorld>,
     );
 
-    fn run(&mut self, (teleports, proximitors, activated, mut level_actions): Self::SystemData) {
-        if activated.0 {
-            for (teleport, proximitor) in (&teleports, &proximitors).join() {
-                if !proximitor.intersections.is_empty() {
-                    level_actions.0.push(teleport.action.clone());
+    fn run(&mut self, (teleports, proximitors, mut bodies, activated, mut level_actions, mut physic_world): Self::SystemData) {
+        for (teleport, proximitor) in (&teleports, &proximitors).join() {
+            match *teleport {
+                ::component::Teleport::Action(ref action) => {
+                    if activated.0 && !proximitor.intersections.is_empty() {
+                        level_actions.0.push(action.clone());
+                    }
+                }
+                ::component::Teleport::Destination(destination) => {
+                    for entity in &proximitor.intersections {
+                        if let Some(body) = bodies.get_mut(*entity) {
+                            let body = body.get_mut(&mut physic_world);
+                            let mut position = body.position().clone();
+                            position.translation.vector = destination;
+                            body.set_transformation(position);
+                        }
+                    }
                 }
             }
         }
@@ -0,0 +1,33 @@
+use specs::Join;
+
+/// Set the next wave as coming when its delay is over or all monsters are killed
+///
+/// The wave is created by GameSystem
+pub struct WaveSystem;
+
+impl<'a> ::specs::System<'a> for WaveSystem {
+    type SystemData = (
+        ::specs::ReadStorage<'a, ::component::Attracted>,
+        ::specs::ReadStorage<'a, ::component::Avoider>,
+        ::specs::ReadStorage<'a, ::component::Bouncer>,
+        ::specs::ReadStorage<'a, ::component::Motionless>,
+        ::specs::Fetch<'a, ::resource::UpdateTime>,
+        ::specs::FetchMut<'a, ::resource::Waves>,
+    );
+
+    fn run(&mut self, (attracted, avoider, bouncer, motionless, update_time, mut waves): Self::SystemData) {
+        if waves.remaining() == 0 || waves.coming {
+            return;
+        }
+
+        waves.timer += update_time.0;
+        let cleared = attracted.join().next().is_none()
+            && avoider.join().next().is_none()
+            && bouncer.join().next().is_none()
+            && motionless.join().next().is_none();
+        let delay = waves.waves[waves.next].0;
+        if cleared || waves.timer >= delay {
+            waves.coming = true;
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use specs::Join;
+
+/// Move the player and moving entities crossing a wrapped border to the opposite border
+pub struct WrapSystem;
+
+impl<'a> ::specs::System<'a> for WrapSystem {
+    type SystemData = (
+        ::specs::ReadStorage<'a, ::component::Player>,
+        ::specs::ReadStorage<'a, ::component::Momentum>,
+        ::specs::WriteStorage<'a, ::component::PhysicBody>,
+        ::specs::FetchMut<'a, ::resource::PhysicWorld>,
+        ::specs::Fetch<'a, ::resource::Maze>,
+        ::specs::Entities<'a>,
+    );
+
+    fn run(
+        &mut self,
+        (players, momentums, mut bodies, mut physic_world, maze, entities): Self::SystemData,
+    ) {
+        let period = match *maze {
+            ::resource::Maze::Maze2D(ref maze) if maze.wrap => ::na::Vector2::new(
+                maze.size[0] as f32 * maze.scale[0],
+                maze.size[1] as f32 * maze.scale[1],
+            ),
+            _ => return,
+        };
+
+        for (body, entity) in (&mut bodies, &*entities).join() {
+            if players.get(entity).is_none() && momentums.get(entity).is_none() {
+                continue;
+            }
+
+            let body = body.get_mut(&mut physic_world);
+            let mut position = body.position().clone();
+            let mut wrapped = false;
+            for i in 0..2 {
+                let coord = position.translation.vector[i];
+                if coord < 0.0 || coord >= period[i] {
+                    position.translation.vector[i] = coord - (coord / period[i]).floor() * period[i];
+                    wrapped = true;
+                }
+            }
+            if wrapped {
+                body.set_transformation(position);
+            }
+        }
+    }
+}
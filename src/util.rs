@@ -161,6 +161,7 @@ impl Benchmarker {
     }
 }
 
+#[macro_export]
 macro_rules! try_multiple_time {
     ($e:expr, $n:expr, $s:expr) => (
         {